@@ -0,0 +1,64 @@
+//! 使用 `arc_gc::bench_utils` 构造的典型对象图测量 `collect` 的耗时
+//! 运行：`cargo bench --bench collect_graphs --features bench-utils`
+
+use arc_gc::{
+    arc::GCArc,
+    bench_utils::{build_cyclic, build_linear, build_random_graph, SimpleNode},
+    gc::GC,
+};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+
+const OBJECT_COUNT: usize = 20_000;
+
+/// 在GC中构造一种对象图并返回其根
+type Build = fn(&mut GC<SimpleNode>) -> GCArc<SimpleNode>;
+
+/// 构造图并先回收一次其中的垃圾，返回只剩存活对象的GC和图的根
+fn setup(build: Build) -> (GC<SimpleNode>, GCArc<SimpleNode>) {
+    let mut gc = GC::new();
+    gc.set_auto_collect(false);
+    let root = build(&mut gc);
+    gc.collect();
+    (gc, root)
+}
+
+/// 对每种图分别测量根仍存活时的一次回收（只标记），以及丢弃根之后回收全部对象的一次回收
+fn bench_graphs(c: &mut Criterion) {
+    let graphs: [(&str, Build); 3] = [
+        ("linear", |gc| build_linear(gc, OBJECT_COUNT)),
+        ("cyclic", |gc| build_cyclic(gc, OBJECT_COUNT)),
+        ("random", |gc| {
+            build_random_graph(gc, OBJECT_COUNT, OBJECT_COUNT * 2, 42)
+        }),
+    ];
+
+    let mut group = c.benchmark_group("collect");
+    group.sample_size(20);
+    for (name, build) in graphs {
+        group.bench_function(format!("{name}/mark"), |b| {
+            b.iter_batched(
+                || setup(build),
+                |(mut gc, root)| {
+                    gc.collect();
+                    (gc, root)
+                },
+                BatchSize::LargeInput,
+            )
+        });
+        group.bench_function(format!("{name}/sweep_all"), |b| {
+            b.iter_batched(
+                || setup(build).0,
+                |mut gc| {
+                    gc.collect();
+                    assert_eq!(gc.object_count(), 0);
+                    gc
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_graphs);
+criterion_main!(benches);
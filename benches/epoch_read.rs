@@ -0,0 +1,84 @@
+//! 比较 `EpochSlot` 的借用读取与 `GCSlot::load`、`GCArcWeak::upgrade` 的读取开销
+//! 运行：`cargo bench --bench epoch_read --features epoch`
+
+use std::{collections::VecDeque, hint::black_box, thread};
+
+use arc_gc::{
+    arc::{GCArc, GCArcWeak, GCSlot},
+    epoch::{pin, EpochSlot},
+    traceable::GCTraceable,
+};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+const READER_THREADS: usize = 4;
+const READS_PER_THREAD: usize = 10_000;
+
+struct Leaf(u64);
+
+impl GCTraceable<Leaf> for Leaf {
+    fn collect(&self, _queue: &mut VecDeque<GCArcWeak<Leaf>>) {}
+}
+
+/// 在 `READER_THREADS` 个线程上并发执行 `read`，放大引用计数所在缓存行上的争用
+fn contended(read: impl Fn() -> u64 + Sync) -> u64 {
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..READER_THREADS)
+            .map(|_| {
+                s.spawn(|| {
+                    let mut sum = 0u64;
+                    for _ in 0..READS_PER_THREAD {
+                        sum = sum.wrapping_add(read());
+                    }
+                    sum
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).sum()
+    })
+}
+
+fn bench_single_thread(c: &mut Criterion) {
+    let arc = GCArc::new(Leaf(1));
+    let weak = arc.as_weak();
+    let slot = GCSlot::new(arc.clone());
+    let epoch_slot = EpochSlot::new(arc.clone());
+
+    let mut group = c.benchmark_group("read");
+    group.bench_function("gc_slot_load", |b| {
+        b.iter(|| black_box(slot.load().as_ref().0))
+    });
+    group.bench_function("weak_upgrade", |b| {
+        b.iter(|| black_box(weak.upgrade().unwrap().as_ref().0))
+    });
+    group.bench_function("epoch_slot_pin_per_read", |b| {
+        b.iter(|| black_box(epoch_slot.read(|arc| arc.as_ref().0)))
+    });
+    group.bench_function("epoch_slot_shared_guard", |b| {
+        let guard = pin();
+        b.iter(|| black_box(epoch_slot.load(&guard).as_ref().0))
+    });
+    group.finish();
+}
+
+fn bench_contended(c: &mut Criterion) {
+    let arc = GCArc::new(Leaf(1));
+    let weak = arc.as_weak();
+    let slot = GCSlot::new(arc.clone());
+    let epoch_slot = EpochSlot::new(arc.clone());
+
+    let mut group = c.benchmark_group("read_contended");
+    group.sample_size(20);
+    group.bench_function("gc_slot_load", |b| {
+        b.iter(|| contended(|| slot.load().as_ref().0))
+    });
+    group.bench_function("weak_upgrade", |b| {
+        b.iter(|| contended(|| weak.upgrade().unwrap().as_ref().0))
+    });
+    group.bench_function("epoch_slot_pin_per_read", |b| {
+        b.iter(|| contended(|| epoch_slot.read(|arc| arc.as_ref().0)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_single_thread, bench_contended);
+criterion_main!(benches);
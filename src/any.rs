@@ -0,0 +1,258 @@
+use std::{any::Any, collections::VecDeque, marker::PhantomData, ops::Deref};
+
+use crate::{
+    arc::{GCArc, GCArcWeak},
+    error::DowncastError,
+    gc::GC,
+    traceable::GCTraceable,
+};
+
+/// 可被类型擦除的GC对象需要实现的trait
+/// 与 `GCTraceable` 不同，引用的对象统一为 `GCAnyObject`，因此不同类型的对象可以互相引用，
+/// 由同一个 `GCAny` 回收器追踪跨类型的循环引用。
+pub trait GCAnyTraceable: Any {
+    /// collects all reachable objects and adds them to the provided queue.
+    fn collect(&self, queue: &mut VecDeque<GCArcWeak<GCAnyObject>>);
+
+    /// clears all outgoing references held by this object, see `GCTraceable::clear_edges`.
+    fn clear_edges(&mut self) {}
+}
+
+/// 类型擦除的GC对象，内部保存任意实现了 `GCAnyTraceable` 的值及其类型名
+pub struct GCAnyObject(Box<dyn GCAnyTraceable>, &'static str);
+
+/// 追踪异构对象图的类型擦除回收器
+pub type GCAny = GC<GCAnyObject>;
+
+impl GCAnyObject {
+    pub fn new<U: GCAnyTraceable>(value: U) -> Self {
+        Self(Box::new(value), std::any::type_name::<U>())
+    }
+
+    /// 内部值的类型名，见 `std::any::type_name`
+    pub fn type_name(&self) -> &'static str {
+        self.1
+    }
+
+    /// 内部值是否为类型 `U`
+    pub fn is<U: GCAnyTraceable>(&self) -> bool {
+        let any: &dyn Any = &*self.0;
+        any.is::<U>()
+    }
+
+    /// 尝试将内部值转换为类型 `U` 的引用
+    pub fn downcast_ref<U: GCAnyTraceable>(&self) -> Option<&U> {
+        let any: &dyn Any = &*self.0;
+        any.downcast_ref::<U>()
+    }
+
+    /// 尝试将内部值转换为类型 `U` 的可变引用
+    pub fn downcast_mut<U: GCAnyTraceable>(&mut self) -> Option<&mut U> {
+        let any: &mut dyn Any = &mut *self.0;
+        any.downcast_mut::<U>()
+    }
+
+    /// 将内部值转换为类型 `U` 的引用，类型不匹配时返回包含实际类型名和请求类型名的错误
+    pub fn downcast_or_err<U: GCAnyTraceable>(&self) -> Result<&U, DowncastError> {
+        self.downcast_ref::<U>().ok_or(DowncastError {
+            requested: std::any::type_name::<U>(),
+            actual: self.type_name(),
+        })
+    }
+}
+
+impl GCTraceable<GCAnyObject> for GCAnyObject {
+    fn collect(&self, queue: &mut VecDeque<GCArcWeak<GCAnyObject>>) {
+        self.0.collect(queue);
+    }
+
+    fn clear_edges(&mut self) {
+        self.0.clear_edges();
+    }
+}
+
+impl GCArc<GCAnyObject> {
+    /// 创建一个新的类型擦除对象
+    pub fn new_any<U: GCAnyTraceable>(value: U) -> Self {
+        GCArc::new(GCAnyObject::new(value))
+    }
+
+    /// 尝试将对象转换为类型 `U` 的引用
+    pub fn downcast_ref<U: GCAnyTraceable>(&self) -> Option<&U> {
+        self.as_ref().downcast_ref::<U>()
+    }
+
+    /// 尝试将对象转换为类型 `U` 的可变引用
+    /// 与 `try_as_mut` 相同，只有当前是唯一引用（强引用数为1且不存在弱引用）时才返回，
+    /// 因此不会产生别名的可变引用；共享对象需要借助内部可变性修改
+    pub fn downcast_mut<U: GCAnyTraceable>(&mut self) -> Option<&mut U> {
+        self.try_as_mut()?.downcast_mut::<U>()
+    }
+
+    /// 将对象转换为类型 `U` 的引用，见 `GCAnyObject::downcast_or_err`
+    pub fn downcast_or_err<U: GCAnyTraceable>(&self) -> Result<&U, DowncastError> {
+        self.as_ref().downcast_or_err::<U>()
+    }
+}
+
+impl GCArcWeak<GCAnyObject> {
+    /// 升级弱引用并检查对象的具体类型，只有对象存活且类型为 `U` 时才返回带类型的视图，
+    /// 对象已被释放或类型不匹配时返回 `None`
+    pub fn try_downcast<U: GCAnyTraceable>(&self) -> Option<GCAnyRef<U>> {
+        let arc = self.upgrade()?;
+        if !arc.as_ref().is::<U>() {
+            return None;
+        }
+        Some(GCAnyRef {
+            arc,
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// 已确认具体类型为 `U` 的类型擦除对象的强引用视图
+/// 持有对象的强引用，在视图存在期间对象保持存活
+pub struct GCAnyRef<U: GCAnyTraceable> {
+    arc: GCArc<GCAnyObject>,
+    _marker: PhantomData<fn() -> U>,
+}
+
+impl<U: GCAnyTraceable> GCAnyRef<U> {
+    /// 取回类型擦除的强引用
+    pub fn into_arc(self) -> GCArc<GCAnyObject> {
+        self.arc
+    }
+}
+
+impl<U: GCAnyTraceable> Deref for GCAnyRef<U> {
+    type Target = U;
+
+    fn deref(&self) -> &U {
+        // 类型已在构造时检查
+        self.arc
+            .downcast_ref::<U>()
+            .expect("GCAnyRef type was checked on construction")
+    }
+}
+
+impl GC<GCAnyObject> {
+    /// 创建一个新的类型擦除对象并自动添加到GC中
+    pub fn create_any<U: GCAnyTraceable>(&mut self, value: U) -> GCArc<GCAnyObject> {
+        self.create(GCAnyObject::new(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    struct Module {
+        functions: RefCell<Vec<GCArcWeak<GCAnyObject>>>,
+    }
+
+    impl GCAnyTraceable for Module {
+        fn collect(&self, queue: &mut VecDeque<GCArcWeak<GCAnyObject>>) {
+            if let Ok(functions) = self.functions.try_borrow() {
+                queue.extend(functions.iter().cloned());
+            }
+        }
+    }
+
+    struct Function {
+        module: RefCell<Option<GCArcWeak<GCAnyObject>>>,
+    }
+
+    impl GCAnyTraceable for Function {
+        fn collect(&self, queue: &mut VecDeque<GCArcWeak<GCAnyObject>>) {
+            if let Ok(module) = self.module.try_borrow() {
+                queue.extend(module.iter().cloned());
+            }
+        }
+    }
+
+    #[test]
+    fn test_cross_type_cycle() {
+        let mut gc = GCAny::new();
+        gc.set_auto_collect(false);
+
+        let module = gc.create_any(Module {
+            functions: RefCell::new(Vec::new()),
+        });
+        let function = gc.create_any(Function {
+            module: RefCell::new(None),
+        });
+
+        // 构造跨类型的循环引用：Module -> Function -> Module
+        module
+            .downcast_ref::<Module>()
+            .unwrap()
+            .functions
+            .borrow_mut()
+            .push(function.as_weak());
+        *function
+            .downcast_ref::<Function>()
+            .unwrap()
+            .module
+            .borrow_mut() = Some(module.as_weak());
+        assert!(module.as_ref().is::<Module>());
+        assert!(module.downcast_ref::<Function>().is_none());
+
+        let function_weak = function.as_weak();
+        assert!(function_weak.try_downcast::<Module>().is_none());
+        assert!(function_weak
+            .try_downcast::<Function>()
+            .unwrap()
+            .module
+            .borrow()
+            .is_some());
+
+        drop(function);
+        gc.collect();
+        assert_eq!(gc.object_count(), 2);
+
+        drop(module);
+        gc.collect();
+        assert_eq!(gc.object_count(), 0);
+    }
+
+    #[test]
+    fn test_downcast_or_err() {
+        let function = GCArc::new_any(Function {
+            module: RefCell::new(None),
+        });
+        assert!(function.downcast_or_err::<Function>().is_ok());
+
+        let err = function.downcast_or_err::<Module>().err().unwrap();
+        assert!(err.actual.ends_with("Function"));
+        assert!(err.requested.ends_with("Module"));
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "cannot downcast GC object of type `{}` to `{}`",
+                err.actual, err.requested
+            )
+        );
+    }
+
+    #[test]
+    fn test_downcast_mut_requires_unique() {
+        let mut function = GCArc::new_any(Function {
+            module: RefCell::new(None),
+        });
+        assert!(function.downcast_mut::<Module>().is_none());
+        assert!(function.downcast_mut::<Function>().is_some());
+
+        // 存在其他引用时无法取得可变引用，避免两个句柄同时持有别名的 `&mut`
+        let mut alias = function.clone();
+        assert!(function.downcast_mut::<Function>().is_none());
+        assert!(alias.downcast_mut::<Function>().is_none());
+        drop(alias);
+
+        let weak = function.as_weak();
+        assert!(function.downcast_mut::<Function>().is_none());
+        drop(weak);
+        assert!(function.downcast_mut::<Function>().is_some());
+    }
+}
@@ -1,14 +1,63 @@
 use std::{
+    cell::{RefCell, RefMut},
     collections::VecDeque,
-    sync::{atomic::AtomicUsize, Arc, Weak},
+    fmt::{self, Display},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    mem::MaybeUninit,
+    ops::{Deref, DerefMut},
+    sync::{
+        atomic::AtomicU64, Arc, Mutex, MutexGuard, PoisonError, RwLock, RwLockWriteGuard, Weak,
+    },
 };
 
-use crate::traceable::GCTraceable;
+use crate::{error::WeakUpgradeError, traceable::GCTraceable};
+
+use self::sync::{AtomicBool, AtomicPtr, AtomicUsize};
+
+/// 对象与 `GCSlot` 上的原子变量和锁。以 `RUSTFLAGS="--cfg loom" cargo test --lib loom` 运行时替换为 loom 的实现，
+/// 由 `loom_tests` 中的模型穷举线程交错。全局静态变量和 `Arc` 自身的引用计数不受 loom 控制，仍使用标准库的实现
+mod sync {
+    #[cfg(all(test, loom))]
+    pub(crate) use loom::{
+        hint::spin_loop,
+        sync::{
+            atomic::{AtomicBool, AtomicPtr, AtomicUsize},
+            Mutex,
+        },
+        thread::yield_now,
+    };
+    #[cfg(not(all(test, loom)))]
+    pub(crate) use std::{
+        hint::spin_loop,
+        sync::{
+            atomic::{AtomicBool, AtomicPtr, AtomicUsize},
+            Mutex,
+        },
+        thread::yield_now,
+    };
+}
+
+/// 全局对象id计数器，每创建一个 `GCWrapper` 加1
+static NEXT_OBJECT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// 全局访问时钟，每次创建对象或升级弱引用时加1，用于记录对象的最近访问时间
+#[cfg(feature = "access-tracking")]
+static ACCESS_CLOCK: AtomicU64 = AtomicU64::new(0);
 
 /// GCWrapper 包装器，包含被垃圾回收的对象和附加的GC计数
+///
+/// 内存布局约定：`#[repr(C)]`，`value` 始终是第一个字段，位于偏移量0处，
+/// 因此 `*const GCWrapper<T>` 可以直接转换为 `*const T`。其余字段的顺序不属于公开约定。
+#[repr(C)]
 pub struct GCWrapper<T: GCTraceable<T> + 'static> {
     value: T,
     pub(crate) attached_gc_count: AtomicUsize,
+    pub(crate) marked: AtomicBool,
+    pub(crate) pinned: AtomicBool,
+    #[cfg(feature = "access-tracking")]
+    last_access: AtomicU64,
+    id: u64,
 }
 
 impl<T: GCTraceable<T> + 'static> GCWrapper<T> {
@@ -16,6 +65,13 @@ impl<T: GCTraceable<T> + 'static> GCWrapper<T> {
         Self {
             value,
             attached_gc_count: AtomicUsize::new(0),
+            marked: AtomicBool::new(false),
+            pinned: AtomicBool::new(false),
+            #[cfg(feature = "access-tracking")]
+            last_access: AtomicU64::new(
+                ACCESS_CLOCK.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+            ),
+            id: NEXT_OBJECT_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
         }
     }
 
@@ -26,6 +82,23 @@ impl<T: GCTraceable<T> + 'static> GCWrapper<T> {
     pub fn value_mut(&mut self) -> &mut T {
         &mut self.value
     }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// 某个GC不再追踪此对象时调用，将 `attached_gc_count` 减1
+    /// 调用者必须确实追踪过此对象（即对象在其追踪列表中），计数不可能为0
+    pub(crate) fn release_attachment(&self) {
+        let previous = self
+            .attached_gc_count
+            .fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+        assert!(
+            previous > 0,
+            "attached_gc_count underflow: object {} was released by a GC that did not track it",
+            self.id
+        );
+    }
 }
 
 #[allow(dead_code)]
@@ -34,6 +107,24 @@ pub trait GCRef {
     fn weak_ref(&self) -> usize;
 }
 
+/// 被垃圾回收管理的强引用
+///
+/// 丢弃 `GCArc` 会改变对象的强引用计数，可能使对象变为可回收，因此忽略返回的 `GCArc` 会产生警告：
+///
+/// ```compile_fail
+/// #![deny(unused_must_use)]
+/// use std::collections::VecDeque;
+/// use arc_gc::{arc::{GCArc, GCArcWeak}, traceable::GCTraceable};
+///
+/// struct Leaf;
+/// impl GCTraceable<Leaf> for Leaf {
+///     fn collect(&self, _queue: &mut VecDeque<GCArcWeak<Leaf>>) {}
+/// }
+///
+/// let obj = GCArc::new(Leaf);
+/// obj.as_weak().upgrade();
+/// ```
+#[must_use]
 pub struct GCArc<T: GCTraceable<T> + 'static> {
     inner: Arc<GCWrapper<T>>,
 }
@@ -46,7 +137,7 @@ impl<T: GCTraceable<T> + 'static> Into<GCArc<T>> for Arc<GCWrapper<T>> {
 
 impl<T: GCTraceable<T> + 'static> From<GCArc<T>> for Arc<GCWrapper<T>> {
     fn from(gc_arc: GCArc<T>) -> Self {
-        gc_arc.inner
+        gc_arc.into_inner()
     }
 }
 
@@ -60,12 +151,44 @@ where
             inner: Arc::new(GCWrapper::new(obj)),
         }
     }
+
+    /// 将 `std::sync::Arc<T>` 中的值移入新的 `GCArc`，便于将基于 `Arc` 的代码迁移到GC上
+    /// `GCWrapper` 需要拥有值本身，因此只有 `arc` 是唯一强引用时才能成功，否则原样返回 `arc`
+    pub fn from_arc(arc: Arc<T>) -> Result<GCArc<T>, Arc<T>> {
+        Arc::try_unwrap(arc).map(GCArc::new)
+    }
+
+    /// 将值移出到新的 `std::sync::Arc<T>` 中，只有当前是唯一强引用时才能成功，否则原样返回自身
+    /// 被GC追踪的对象总被GC持有一份强引用，需要先 `detach`
+    pub fn into_arc(self) -> Result<Arc<T>, GCArc<T>> {
+        self.try_unwrap().map(Arc::new)
+    }
+
+    /// 创建指向此对象的弱引用
+    /// 注意：不提供缓存弱引用的版本。`Arc::downgrade` 与克隆已有的 `Weak` 开销相近（都是一次弱引用计数的原子操作），
+    /// 而在对象内缓存一个弱引用会使每个对象多占用空间，并使 `is_unique`/`get_mut` 永远失败。
+    #[must_use]
     pub fn as_weak(&self) -> GCArcWeak<T> {
         GCArcWeak {
             inner: Arc::downgrade(&self.inner),
+            #[cfg(feature = "debug")]
+            source: crate::debug::record_weak_source(self.inner.id),
         }
     }
 
+    /// `as_weak` 的简短别名
+    #[must_use]
+    pub fn weak(&self) -> GCArcWeak<T> {
+        self.as_weak()
+    }
+
+    /// 当前指向此对象的每个已登记弱引用的创建位置（调用栈），需先启用 `debug::track_weak_sources`
+    /// 克隆得到的弱引用单独登记，因此结果中的每一项对应一个仍然存在的弱引用
+    #[cfg(feature = "debug")]
+    pub fn weak_sources(&self) -> Vec<String> {
+        crate::debug::weak_sources(self.inner.id)
+    }
+
     pub fn as_ref(&self) -> &T {
         &self.inner.value
     }
@@ -78,18 +201,179 @@ where
         )
     }
 
+    #[must_use]
     pub fn try_as_mut(&mut self) -> Option<&mut T> {
         Arc::get_mut(&mut self.inner).map(|wrapper| &mut wrapper.value)
     }
 
+    /// 对象创建时分配的唯一id，在对象的整个生命周期内不变，且对象被回收后也不会被复用，
+    /// 可以安全地用作外部表的键
+    pub fn id(&self) -> u64 {
+        self.inner.id
+    }
+
+    /// GC之外持有的强引用数，即强引用计数减去追踪此对象的GC数量
+    pub fn external_strong_count(&self) -> usize {
+        self.strong_ref().saturating_sub(self.tracked_by_count())
+    }
+
+    /// 对象当前是否满足被回收的条件：所有强引用都由GC持有且未被固定
+    /// 不考虑从其他根对象的可达性，也不考虑宽限期等GC内部的根，适用于在测试中代替对强引用计数的硬编码断言
+    pub fn is_collectible(&self) -> bool {
+        self.external_strong_count() == 0 && !self.is_pinned()
+    }
+
+    /// 从此对象出发经由 `GCTraceable::collect` 能否到达 `target`（按对象身份比较，对象总能到达自身），
+    /// 即修改此对象是否可能影响 `target`。最多访问 `max_visits` 个对象，超过上限仍未找到时返回 `false`，见 `gc::reachable`
+    pub fn reaches(&self, target: &GCArc<T>, max_visits: usize) -> bool {
+        crate::gc::reachable(self, &target.as_weak(), max_visits)
+    }
+
+    /// 记录当前的外部强引用数（见 `external_strong_count`），返回的守卫在被丢弃时断言该数值已恢复，
+    /// 用于在测试中发现作用域内泄漏的克隆，代替对强引用计数的硬编码断言
+    #[must_use]
+    pub fn watch(&self) -> RefCountGuard<'_, T> {
+        RefCountGuard {
+            arc: self,
+            expected: self.external_strong_count(),
+        }
+    }
+
+    /// 一个被管理对象实际占用的堆内存（字节）：`GCWrapper<T>` 加上 `Arc` 控制块的开销，
+    /// 比回收器内部使用的 `size_of::<T>() + size_of::<GCArc<T>>()` 估算更准确，可用于评估包装器对小对象的额外开销。
+    /// 假设 `Arc` 的分配由强引用和弱引用两个 `usize` 计数器加上值组成（当前标准库的实现），不包括值自身持有的堆数据
+    pub fn alloc_size(&self) -> usize {
+        let counters = std::alloc::Layout::new::<[std::sync::atomic::AtomicUsize; 2]>();
+        let (layout, _) = counters
+            .extend(std::alloc::Layout::new::<GCWrapper<T>>())
+            .expect("GCWrapper layout overflow");
+        layout.pad_to_align().size()
+    }
+
+    /// 当前追踪此对象的GC数量（`attached_gc_count`）
+    /// 强引用计数超过此值（再扣除被追踪对象之间的强引用边）时，对象在回收中被视为根对象
+    pub fn tracked_by_count(&self) -> usize {
+        self.inner
+            .attached_gc_count
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// 迄今为止创建的对象总数（所有类型共享同一个全局计数器）
+    pub fn creation_count() -> u64 {
+        NEXT_OBJECT_ID.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// 当前 GCArc 是否是唯一引用（强引用数为1且不存在弱引用）
+    pub fn is_unique(&self) -> bool {
+        Arc::strong_count(&self.inner) == 1 && Arc::weak_count(&self.inner) == 0
+    }
+
+    /// 类似 `Arc::make_mut` 的写时复制：若不是唯一引用，则克隆内部值到新的分配中，
+    /// 再返回其可变引用。
+    /// 注意：被GC追踪的对象总会被GC持有一份强引用，因此对已attach的对象调用时通常会复制，
+    /// 且新的分配不会被任何GC追踪。
+    pub fn make_mut(&mut self) -> &mut T
+    where
+        T: Clone,
+    {
+        if !self.is_unique() {
+            *self = GCArc::new(self.as_ref().clone());
+        }
+        &mut Arc::get_mut(&mut self.inner)
+            .expect("GCArc should be unique after clone-on-write")
+            .value
+    }
+
+    /// 对象的标记位是否已被设置，供用户自定义的遍历算法使用
+    pub fn is_marked(&self) -> bool {
+        self.inner.marked.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// 清除对象的标记位
+    pub fn clear_mark(&self) {
+        self.inner
+            .marked
+            .store(false, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// 设置对象是否被固定，被固定的对象永远不会被回收
+    /// 固定的对象在标记阶段被视为根对象，因此其引用的整个子图也会保持存活
+    pub fn set_pinned(&self, pinned: bool) {
+        self.inner
+            .pinned
+            .store(pinned, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// 对象的最近访问时间：创建对象或通过 `GCArcWeak::upgrade` 升级时的全局访问时钟读数
+    /// 只用于比较对象访问的先后，GC内部遍历不会更新此值。需要启用 `access-tracking` feature
+    #[cfg(feature = "access-tracking")]
+    pub fn last_access(&self) -> u64 {
+        self.inner
+            .last_access
+            .load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// 对象是否被固定
+    pub fn is_pinned(&self) -> bool {
+        self.inner.pinned.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
     fn collect(&self, queue: &mut VecDeque<GCArcWeak<T>>) {
-        self.inner.value.collect(queue);
+        crate::traceable::trace_children(&self.inner.value, queue);
     }
 
     pub(crate) fn ptr_eq(a: &GCArc<T>, b: &GCArc<T>) -> bool {
         Arc::ptr_eq(&a.inner, &b.inner)
     }
 
+    /// 仅在 `cond` 为真时克隆引用，避免在热路径上无条件克隆后又丢弃
+    #[must_use]
+    pub fn clone_if(&self, cond: bool) -> Option<GCArc<T>> {
+        cond.then(|| self.clone())
+    }
+
+    /// 判断当前对象是否与 `others` 中的某个引用是同一个对象（按身份而非值比较）
+    pub fn is_any_of(&self, others: &[GCArc<T>]) -> bool {
+        others.iter().any(|other| GCArc::ptr_eq(self, other))
+    }
+
+    /// 判断弱引用 `weak` 是否指向当前对象，无需升级弱引用
+    /// 弱引用会保持其分配不被释放，因此即使 `weak` 已失效，其地址也不会被当前对象复用，此时返回 `false`
+    pub fn same_object_as(&self, weak: &GCArcWeak<T>) -> bool {
+        std::ptr::eq(self.as_wrapper_ptr(), weak.as_wrapper_ptr())
+    }
+
+    /// 获取指向 `GCWrapper<T>` 的指针
+    /// 由于 `GCWrapper` 为 `#[repr(C)]` 且 `value` 为第一个字段，该指针也可以直接转换为 `*const T`
+    #[inline(always)]
+    pub fn as_wrapper_ptr(&self) -> *const GCWrapper<T> {
+        Arc::as_ptr(&self.inner)
+    }
+
+    /// 将对象的内存地址写入 `state`，用于按对象身份（而非值）计算哈希
+    pub fn ptr_hash<H: Hasher>(&self, state: &mut H) {
+        (Arc::as_ptr(&self.inner) as usize).hash(state);
+    }
+
+    /// 若当前是唯一的强引用，则取出内部的值
+    pub(crate) fn try_unwrap(self) -> Result<T, Self> {
+        Arc::try_unwrap(self.into_inner())
+            .map(|wrapper| wrapper.value)
+            .map_err(|inner| GCArc { inner })
+    }
+
+    /// 取出内部的 `Arc`，所有权直接转移，不视为一次强引用的释放
+    pub(crate) fn into_inner(self) -> Arc<GCWrapper<T>> {
+        #[cfg(feature = "debug")]
+        {
+            let this = std::mem::ManuallyDrop::new(self);
+            // SAFETY: `this` 不会再被使用或析构，`inner` 的所有权被转移给调用者
+            unsafe { std::ptr::read(&this.inner) }
+        }
+        #[cfg(not(feature = "debug"))]
+        self.inner
+    }
+
     #[inline(always)]
     pub(crate) fn inner(&self) -> &GCWrapper<T> {
         &self.inner
@@ -101,9 +385,29 @@ where
     T: GCTraceable<T> + 'static,
 {
     fn clone(&self) -> Self {
-        Self {
-            inner: self.inner.clone(),
-        }
+        let inner = self.inner.clone();
+        #[cfg(feature = "debug")]
+        crate::debug::notify_refcount(inner.id, Arc::strong_count(&inner));
+        Self { inner }
+    }
+}
+
+#[cfg(feature = "debug")]
+impl<T> Drop for GCArc<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn drop(&mut self) {
+        crate::debug::notify_refcount(self.inner.id, Arc::strong_count(&self.inner) - 1);
+    }
+}
+
+impl<T> Display for GCArc<T>
+where
+    T: GCTraceable<T> + Display + 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.as_ref(), f)
     }
 }
 
@@ -120,18 +424,188 @@ where
     }
 }
 
+/// 借用或持有 `GCArc` 的写时克隆式引用
+/// 对可能需要、也可能不需要保留对象的API，可以先借用，只有在真正需要所有权时才通过
+/// `into_owned` 克隆（增加引用计数），从而减少原子操作
+pub enum GCCow<'a, T: GCTraceable<T> + 'static> {
+    Borrowed(&'a GCArc<T>),
+    Owned(GCArc<T>),
+}
+
+impl<T> GCCow<'_, T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    /// 获取拥有所有权的 `GCArc`，只有借用时才会克隆
+    pub fn into_owned(self) -> GCArc<T> {
+        match self {
+            GCCow::Borrowed(gc_arc) => gc_arc.clone(),
+            GCCow::Owned(gc_arc) => gc_arc,
+        }
+    }
+
+    /// 获取底层的 `GCArc` 引用
+    pub fn as_arc(&self) -> &GCArc<T> {
+        match self {
+            GCCow::Borrowed(gc_arc) => gc_arc,
+            GCCow::Owned(gc_arc) => gc_arc,
+        }
+    }
+
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self, GCCow::Borrowed(_))
+    }
+}
+
+impl<T> Deref for GCCow<'_, T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.as_arc().as_ref()
+    }
+}
+
+impl<'a, T> From<&'a GCArc<T>> for GCCow<'a, T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn from(gc_arc: &'a GCArc<T>) -> Self {
+        GCCow::Borrowed(gc_arc)
+    }
+}
+
+impl<T> From<GCArc<T>> for GCCow<'_, T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn from(gc_arc: GCArc<T>) -> Self {
+        GCCow::Owned(gc_arc)
+    }
+}
+
+/// `GCArc::watch` 返回的守卫，被丢弃时外部强引用数与创建时不同则panic
+/// 线程已经处于panic中时不再断言，避免掩盖原本的失败
+pub struct RefCountGuard<'a, T: GCTraceable<T> + 'static> {
+    arc: &'a GCArc<T>,
+    expected: usize,
+}
+
+impl<T> RefCountGuard<'_, T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    /// 创建守卫时记录的外部强引用数
+    pub fn expected(&self) -> usize {
+        self.expected
+    }
+}
+
+impl<T> Drop for RefCountGuard<'_, T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            return;
+        }
+        let actual = self.arc.external_strong_count();
+        assert_eq!(
+            actual,
+            self.expected,
+            "external strong count of object {} changed from {} to {} while watched",
+            self.arc.id(),
+            self.expected,
+            actual
+        );
+    }
+}
+
+/// 按对象身份（指针地址）实现 `Hash` 和 `Eq` 的 `GCArc` 包装器
+/// 使 `GCArc<T>` 本身可以保留值语义，由用户在每个集合中自行选择身份语义或值语义
+/// `Ord` 按对象的唯一id（见 `GCArc::id`）比较，与 `Eq` 一致，可用于 `BTreeSet`/`BTreeMap` 中的确定性遍历
+pub struct ById<T: GCTraceable<T> + 'static>(pub GCArc<T>);
+
+impl<T> Clone for ById<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn clone(&self) -> Self {
+        ById(self.0.clone())
+    }
+}
+
+impl<T> Hash for ById<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.ptr_hash(state);
+    }
+}
+
+impl<T> PartialEq for ById<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn eq(&self, other: &Self) -> bool {
+        GCArc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<T> Eq for ById<T> where T: GCTraceable<T> + 'static {}
+
+impl<T> PartialOrd for ById<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for ById<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // id在对象的生命周期内不变且不会被复用，同一对象当且仅当id相同
+        self.0.id().cmp(&other.0.id())
+    }
+}
+
+/// 指向GC对象的弱引用
+///
+/// 基于 `std::sync::Weak` 实现：弱引用存在期间对象的分配不会被释放，其地址也就不会被新对象复用，
+/// 因此失效的弱引用不可能升级到另一个恰好分配在同一地址的对象（不存在ABA问题），无需额外的代数计数。
 pub struct GCArcWeak<T: GCTraceable<T> + 'static> {
     inner: Weak<GCWrapper<T>>,
+    #[cfg(feature = "debug")]
+    source: u64, // 在弱引用来源登记表中的编号，0表示未登记（见 `debug::track_weak_sources`）
 }
 
 impl<T: GCTraceable<T> + 'static> Into<GCArcWeak<T>> for Weak<GCWrapper<T>> {
     fn into(self) -> GCArcWeak<T> {
-        GCArcWeak { inner: self }
+        GCArcWeak {
+            inner: self,
+            #[cfg(feature = "debug")]
+            source: 0,
+        }
     }
 }
 
 impl<T: GCTraceable<T> + 'static> From<GCArcWeak<T>> for Weak<GCWrapper<T>> {
     fn from(gc_arc_weak: GCArcWeak<T>) -> Self {
+        #[cfg(feature = "debug")]
+        {
+            crate::debug::forget_weak_source(gc_arc_weak.source);
+            let this = std::mem::ManuallyDrop::new(gc_arc_weak);
+            // SAFETY: `this` 不会再被使用或析构，`inner` 的所有权被转移给调用者
+            unsafe { std::ptr::read(&this.inner) }
+        }
+        #[cfg(not(feature = "debug"))]
         gc_arc_weak.inner
     }
 }
@@ -141,13 +615,78 @@ impl<T> GCArcWeak<T>
 where
     T: GCTraceable<T> + 'static,
 {
+    /// 升级为强引用；启用 `access-tracking` feature 时同时将对象的最近访问时间更新为当前时刻（见 `GCArc::last_access`）
+    #[must_use]
     pub fn upgrade(&self) -> Option<GCArc<T>> {
-        self.inner.upgrade().map(|inner| GCArc { inner })
+        let strong = self.upgrade_untracked()?;
+        #[cfg(feature = "access-tracking")]
+        strong.inner.last_access.store(
+            ACCESS_CLOCK.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+            std::sync::atomic::Ordering::Relaxed,
+        );
+        Some(strong)
+    }
+
+    /// 仅当对象仍被至少一个GC追踪时升级为强引用
+    /// 被追踪的对象总有GC持有的强引用，因此 `upgrade` 对它们一定成功；此方法用于区分
+    /// "因被GC追踪而存活"与"仅因外部引用而存活"（例如已被 `detach` 或已被回收但仍被外部持有的对象）
+    /// 弱引用追踪模式的GC不计入 `attached_gc_count`，只被这类GC追踪的对象总是返回 `None`
+    #[must_use]
+    pub fn upgrade_if_tracked(&self) -> Option<GCArc<T>> {
+        let strong = self.upgrade()?;
+        if strong
+            .inner
+            .attached_gc_count
+            .load(std::sync::atomic::Ordering::Relaxed)
+            == 0
+        {
+            return None;
+        }
+        Some(strong)
+    }
+
+    /// 升级为强引用但不更新最近访问时间，供GC内部的遍历使用
+    #[must_use]
+    pub(crate) fn upgrade_untracked(&self) -> Option<GCArc<T>> {
+        let inner = self.inner.upgrade()?;
+        #[cfg(feature = "debug")]
+        crate::debug::notify_refcount(inner.id, Arc::strong_count(&inner));
+        Some(GCArc { inner })
     }
 
     pub fn is_valid(&self) -> bool {
         self.inner.strong_count() > 0
     }
+
+    /// 获取指向 `GCWrapper<T>` 的指针，对象已被释放时指针仍然有效但不可解引用
+    #[inline(always)]
+    pub fn as_wrapper_ptr(&self) -> *const GCWrapper<T> {
+        self.inner.as_ptr()
+    }
+
+    /// 临时升级为强引用并以对象的引用调用 `f`，调用结束后立即释放临时的强引用
+    /// 对象已被释放时返回 `None`。用于短暂的读取，避免意外地长期持有升级后的强引用而使垃圾无法回收
+    pub fn with_upgraded<R>(&self, f: impl FnOnce(&T) -> R) -> Option<R> {
+        let strong = self.upgrade()?;
+        Some(f(strong.as_ref()))
+    }
+
+    /// 当前弱引用指向的对象是否可以从 `from` 到达，最多访问 `max_visits` 个对象，见 `gc::reachable`
+    pub fn reachable_from(&self, from: &GCArc<T>, max_visits: usize) -> bool {
+        crate::gc::reachable(from, self, max_visits)
+    }
+
+    /// 升级为强引用并设置对象的标记位，对象已被释放时返回 `None`
+    /// 用于在GC之外手写的可达性遍历，标记位需由调用者通过 `GCArc::clear_mark` 自行重置
+    #[must_use]
+    pub fn upgrade_and_mark(&self) -> Option<GCArc<T>> {
+        let strong = self.upgrade()?;
+        strong
+            .inner
+            .marked
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        Some(strong)
+    }
 }
 
 impl<T> Clone for GCArcWeak<T>
@@ -157,10 +696,110 @@ where
     fn clone(&self) -> Self {
         Self {
             inner: self.inner.clone(),
+            #[cfg(feature = "debug")]
+            source: crate::debug::record_cloned_weak_source(self.source),
         }
     }
 }
 
+#[cfg(feature = "debug")]
+impl<T> Drop for GCArcWeak<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn drop(&mut self) {
+        crate::debug::forget_weak_source(self.source);
+    }
+}
+
+/// 未初始化的值不引用任何对象，使 `GCArc<MaybeUninit<T>>` 可以存在（见 `GCArc::new_uninit`）
+impl<T> GCTraceable<MaybeUninit<T>> for MaybeUninit<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn collect(&self, _queue: &mut VecDeque<GCArcWeak<MaybeUninit<T>>>) {}
+}
+
+impl<T> GCArc<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    /// 分配一个未初始化的对象，用于两阶段构造：先取得指向对象自身的弱引用（`as_weak_init`），
+    /// 通过 `as_mut_ptr` 原地写入完整的值，再以 `assume_init` 得到 `GCArc<T>`。类似 `Arc::new_uninit`。
+    /// 未初始化的对象不能被GC追踪；在 `assume_init` 之前被丢弃时，已写入的值不会被析构
+    pub fn new_uninit() -> GCArc<MaybeUninit<T>> {
+        GCArc::new(MaybeUninit::uninit())
+    }
+}
+
+impl<T> GCArc<MaybeUninit<T>>
+where
+    T: GCTraceable<T> + 'static,
+{
+    /// 指向未初始化的值的指针，用于原地写入
+    /// 写入时不能存在其他对该值的引用（例如通过 `as_ref` 或 `get_mut` 取得的引用）
+    pub fn as_mut_ptr(&self) -> *mut T {
+        // `GCWrapper` 为 `#[repr(C)]` 且 `value` 位于偏移量0处；
+        // 指针直接来自 `Arc` 的分配而不是共享引用，可以用于写入
+        Arc::as_ptr(&self.inner) as *mut T
+    }
+
+    /// 创建指向初始化完成后的对象的弱引用，可以在初始化期间写入值本身
+    ///
+    /// # Safety
+    ///
+    /// 在 `assume_init` 之前不能升级返回的弱引用（包括通过GC的任何遍历），否则会读取未初始化的内存
+    pub unsafe fn as_weak_init(&self) -> GCArcWeak<T> {
+        let weak = Arc::downgrade(&self.inner);
+        // SAFETY: `GCWrapper<MaybeUninit<T>>` 与 `GCWrapper<T>` 的布局相同（`#[repr(C)]`，`MaybeUninit<T>` 与 `T` 布局相同）
+        unsafe { Weak::from_raw(Weak::into_raw(weak) as *const GCWrapper<T>) }.into()
+    }
+
+    /// 将已初始化的对象转换为 `GCArc<T>`
+    ///
+    /// # Safety
+    ///
+    /// 值必须已经被完整初始化，否则之后对对象的任何访问都是未定义行为
+    pub unsafe fn assume_init(self) -> GCArc<T> {
+        let raw = Arc::into_raw(self.into_inner()) as *const GCWrapper<T>;
+        // SAFETY: 布局相同，见 `as_weak_init`；调用者保证值已经初始化
+        unsafe { Arc::from_raw(raw) }.into()
+    }
+}
+
+impl<T> TryFrom<&GCArcWeak<T>> for GCArc<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    type Error = WeakUpgradeError;
+
+    /// 等同于 `upgrade()`，对象已被释放时返回错误，便于在 `?` 转换链中使用
+    fn try_from(weak: &GCArcWeak<T>) -> Result<Self, Self::Error> {
+        weak.upgrade().ok_or(WeakUpgradeError)
+    }
+}
+
+impl<T> TryFrom<GCArcWeak<T>> for GCArc<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    type Error = WeakUpgradeError;
+
+    fn try_from(weak: GCArcWeak<T>) -> Result<Self, Self::Error> {
+        GCArc::try_from(&weak)
+    }
+}
+
+impl<T> Default for GCArcWeak<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    /// 创建一个不指向任何对象的弱引用，永远无法升级，便于为包含弱引用字段的类型派生 `Default`
+    fn default() -> Self {
+        Weak::new().into()
+    }
+}
+
 impl<T> GCRef for GCArcWeak<T>
 where
     T: GCTraceable<T> + 'static,
@@ -173,3 +812,358 @@ where
         self.inner.weak_count()
     }
 }
+
+/// 带有效性缓存的弱引用，用于反复访问同一条弱引用边的热点路径
+/// 不持有目标的强引用，因此不会使目标成为根对象，也不影响环的回收。
+/// 目标被释放后不可能复活，第一次发现目标已被释放时记录下来，之后的 `get` 直接返回 `None` 而无需访问引用计数；
+/// 目标存活时的升级不更新最近访问时间（见 `GCArc::last_access`）。
+pub struct CachedWeak<T: GCTraceable<T> + 'static> {
+    weak: GCArcWeak<T>,
+    dead: std::sync::atomic::AtomicBool, // 只是缓存，不在loom模型中
+}
+
+impl<T> CachedWeak<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    pub fn new(weak: GCArcWeak<T>) -> Self {
+        Self {
+            weak,
+            dead: std::sync::atomic::AtomicBool::new(false),
+        }
+    }
+
+    /// 升级为强引用，目标已被释放时返回 `None`
+    #[must_use]
+    pub fn get(&self) -> Option<GCArc<T>> {
+        if self.dead.load(std::sync::atomic::Ordering::Relaxed) {
+            return None;
+        }
+        let strong = self.weak.upgrade_untracked();
+        if strong.is_none() {
+            self.dead.store(true, std::sync::atomic::Ordering::Relaxed);
+        }
+        strong
+    }
+
+    /// 目标是否仍然存活
+    pub fn is_valid(&self) -> bool {
+        !self.dead.load(std::sync::atomic::Ordering::Relaxed) && self.weak.is_valid()
+    }
+
+    /// 将缓存指向新的弱引用
+    pub fn set(&mut self, weak: GCArcWeak<T>) {
+        self.weak = weak;
+        *self.dead.get_mut() = false;
+    }
+
+    /// 获取底层的弱引用
+    pub fn weak(&self) -> &GCArcWeak<T> {
+        &self.weak
+    }
+}
+
+impl<T> From<GCArcWeak<T>> for CachedWeak<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn from(weak: GCArcWeak<T>) -> Self {
+        Self::new(weak)
+    }
+}
+
+/// `GCSlot::swap` 等待读者退出时自旋的次数上限，超过后改为在锁上阻塞新的读者
+#[cfg(not(all(test, loom)))]
+const SLOT_SWAP_SPINS: usize = 1024;
+/// loom 模型中缩短自旋，使 `swap` 尽快走到持锁阻塞新读者的路径
+#[cfg(all(test, loom))]
+const SLOT_SWAP_SPINS: usize = 1;
+
+/// 可原子替换的强引用槽，用于在并发对象图中修改强引用边
+/// 槽始终持有一个强引用。`load` 不加锁；`swap` 需要等待正在进行的 `load` 完成，
+/// 短暂自旋后仍有读者时会阻塞新的 `load` 直到读者全部退出，因此 `swap` 可能阻塞，但不会被持续的读取饿死
+pub struct GCSlot<T: GCTraceable<T> + 'static> {
+    ptr: AtomicPtr<GCWrapper<T>>,
+    readers: AtomicUsize,
+    draining: AtomicBool,        // 是否有 `swap` 正在阻塞新的读者
+    drain_lock: sync::Mutex<()>, // `swap` 阻塞新的读者期间持有的锁，`load` 在其上等待
+    _marker: PhantomData<GCArc<T>>,
+}
+
+impl<T> GCSlot<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    pub fn new(value: GCArc<T>) -> Self {
+        Self {
+            ptr: AtomicPtr::new(Arc::into_raw(value.into_inner()) as *mut GCWrapper<T>),
+            readers: AtomicUsize::new(0),
+            draining: AtomicBool::new(false),
+            drain_lock: sync::Mutex::new(()),
+            _marker: PhantomData,
+        }
+    }
+
+    /// 获取槽中当前引用的一个新的强引用
+    /// 没有 `swap` 在等待读者退出时不加锁；否则等待该 `swap` 完成
+    pub fn load(&self) -> GCArc<T> {
+        // 内存序（参照 `std::sync::Arc`：增加计数前获取、减少计数时发布）：
+        // - 登记读者用 `Acquire`：若 `swap` 检查读者的读-改-写先于本次登记，则与其 `Release` 同步，之后读到的一定是新指针
+        // - `draining` 只影响是否退避重试，不影响安全性，用 `Relaxed`
+        loop {
+            self.readers
+                .fetch_add(1, std::sync::atomic::Ordering::Acquire);
+            if !self.draining.load(std::sync::atomic::Ordering::Relaxed) {
+                break;
+            }
+            // 有 `swap` 在等待读者退出：撤销登记，在锁上等待它完成后重试；撤销前没有访问指针，用 `Relaxed`
+            self.readers
+                .fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+            drop(
+                self.drain_lock
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner),
+            );
+        }
+        // `Acquire`：与 `swap` 替换指针时的 `Release` 配对，保证新对象的内容可见
+        let ptr = self.ptr.load(std::sync::atomic::Ordering::Acquire);
+        // `Arc` 的引用计数不受loom控制：在读取指针与增加引用计数之间插入调度点，使模型覆盖 `swap` 插入其间的情况
+        #[cfg(all(test, loom))]
+        sync::yield_now();
+        // SAFETY: `ptr` 来自 `Arc::into_raw`，且槽仍持有它的一个强引用：
+        // 替换掉它的 `swap` 会等待 `readers` 归零后才释放旧引用，因此此处对象一定存活。
+        let inner = unsafe {
+            Arc::increment_strong_count(ptr);
+            Arc::from_raw(ptr)
+        };
+        // `Release`：使上面的引用计数增加先于 `swap` 读到读者归零之后交还旧引用
+        self.readers
+            .fetch_sub(1, std::sync::atomic::Ordering::Release);
+        #[cfg(feature = "debug")]
+        crate::debug::notify_refcount(inner.id, Arc::strong_count(&inner));
+        GCArc { inner }
+    }
+
+    /// 是否没有已登记的读者，供 `swap` 在替换指针后检查
+    /// 用读-改-写而不是普通读取：读-改-写总是读到计数的最新值，因此与 `load` 的登记之间有确定的先后，
+    /// 不需要 `SeqCst` 来排除"`swap` 看不到读者、读者又读到旧指针"的情况。
+    /// `AcqRel`：`Release` 使之前的指针替换对之后登记的读者可见，`Acquire` 与读者退出时的 `Release` 同步
+    fn no_readers(&self) -> bool {
+        self.readers
+            .fetch_add(0, std::sync::atomic::Ordering::AcqRel)
+            == 0
+    }
+
+    /// 原子地将槽中的引用替换为 `new`，返回旧的引用
+    /// 替换本身是原子的，但返回前需要等待可能读到旧引用的 `load` 完成，因此可能阻塞
+    pub fn swap(&self, new: GCArc<T>) -> GCArc<T> {
+        let new_ptr = Arc::into_raw(new.into_inner()) as *mut GCWrapper<T>;
+        // `AcqRel`：`Release` 发布新对象，`Acquire` 获取此前发布的旧对象以便交还给调用者
+        let old = self.ptr.swap(new_ptr, std::sync::atomic::Ordering::AcqRel);
+        // 等待可能读到旧指针的 `load` 完成引用计数的增加。
+        // 读者计数是所有读者共享的，持续的读取可能使它一直不为0：自旋一段时间后，
+        // 持锁阻塞新的读者，只等待已登记的读者退出。
+        let mut spins = 0;
+        while !self.no_readers() {
+            if spins < SLOT_SWAP_SPINS {
+                spins += 1;
+                sync::spin_loop();
+                continue;
+            }
+            let _guard = self
+                .drain_lock
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            self.draining
+                .store(true, std::sync::atomic::Ordering::Relaxed);
+            while !self.no_readers() {
+                sync::yield_now();
+            }
+            self.draining
+                .store(false, std::sync::atomic::Ordering::Relaxed);
+            break;
+        }
+        // SAFETY: `old` 来自 `Arc::into_raw`，槽持有的强引用在此处转交给调用者
+        GCArc {
+            inner: unsafe { Arc::from_raw(old) },
+        }
+    }
+
+    /// 将槽中的引用替换为 `new`，并释放旧的引用
+    pub fn store(&self, new: GCArc<T>) {
+        drop(self.swap(new));
+    }
+}
+
+impl<T> Drop for GCSlot<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn drop(&mut self) {
+        // SAFETY: 槽独占持有 `ptr` 对应的一个强引用，此时不会再有并发访问
+        let ptr = self.ptr.load(std::sync::atomic::Ordering::Relaxed);
+        drop(unsafe { Arc::from_raw(ptr) });
+    }
+}
+
+/// 提供内部可变性的包装类型，配合 `GCArc::lock` 在不要求唯一引用的情况下修改对象
+///
+/// 本库为 `Mutex<U>`、`RwLock<U>`（写锁）和 `RefCell<U>`（可变借用）实现了此trait；
+/// 对象类型通常是包装了这些类型的结构体，可以将实现委托给内部字段。
+pub trait InteriorMut {
+    type Target;
+    type Guard<'a>: DerefMut<Target = Self::Target>
+    where
+        Self: 'a;
+
+    /// 获取内部值的可变访问。锁被毒化时仍返回其中的值；`RefCell` 已被借用时panic
+    fn lock(&self) -> Self::Guard<'_>;
+}
+
+impl<U> InteriorMut for Mutex<U> {
+    type Target = U;
+    type Guard<'a>
+        = MutexGuard<'a, U>
+    where
+        U: 'a;
+
+    fn lock(&self) -> Self::Guard<'_> {
+        Mutex::lock(self).unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<U> InteriorMut for RwLock<U> {
+    type Target = U;
+    type Guard<'a>
+        = RwLockWriteGuard<'a, U>
+    where
+        U: 'a;
+
+    fn lock(&self) -> Self::Guard<'_> {
+        self.write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<U> InteriorMut for RefCell<U> {
+    type Target = U;
+    type Guard<'a>
+        = RefMut<'a, U>
+    where
+        U: 'a;
+
+    fn lock(&self) -> Self::Guard<'_> {
+        self.borrow_mut()
+    }
+}
+
+impl<T> GCArc<T>
+where
+    T: GCTraceable<T> + InteriorMut + 'static,
+{
+    /// 通过对象的内部可变性获取可变访问，不要求当前是唯一引用（与 `try_as_mut` 不同）
+    pub fn lock(&self) -> T::Guard<'_> {
+        InteriorMut::lock(self.as_ref())
+    }
+}
+
+/// loom 模型，运行：`RUSTFLAGS="--cfg loom" cargo test --lib loom --release`
+/// `Arc` 自身的引用计数不受 loom 控制，这些模型穷举的是 `GCSlot` 和对象上的原子变量与 `clone`/`drop`/`upgrade`/`as_weak` 交错的情况
+#[cfg(all(test, loom))]
+mod loom_tests {
+    use loom::{sync::atomic::Ordering, thread};
+
+    use super::*;
+
+    /// 被释放时设置共享的标志，用于在不访问对象的情况下判断它是否已被释放
+    struct Leaf(Arc<std::sync::atomic::AtomicBool>);
+
+    impl Leaf {
+        fn new() -> Self {
+            Leaf(Arc::default())
+        }
+    }
+
+    impl Drop for Leaf {
+        fn drop(&mut self) {
+            self.0.store(true, std::sync::atomic::Ordering::Relaxed);
+        }
+    }
+
+    impl GCTraceable<Leaf> for Leaf {
+        fn collect(&self, _queue: &mut VecDeque<GCArcWeak<Leaf>>) {}
+    }
+
+    #[test]
+    fn loom_clone_drop_upgrade() {
+        loom::model(|| {
+            let arc = GCArc::new(Leaf::new());
+            arc.inner.attached_gc_count.store(1, Ordering::Relaxed);
+            let weak = arc.as_weak();
+            let clone = arc.clone();
+            let t = thread::spawn(move || {
+                let weak = clone.as_weak();
+                drop(clone);
+                // 主线程仍持有 `arc`，升级一定成功
+                weak.upgrade_and_mark().is_some()
+            });
+            let tracked = weak.upgrade_if_tracked();
+            arc.inner.release_attachment();
+            assert!(tracked.is_some());
+            assert!(t.join().unwrap());
+            assert!(arc.is_marked());
+            drop(tracked);
+            drop(arc);
+            assert!(!weak.is_valid());
+            assert!(weak.upgrade().is_none());
+        });
+    }
+
+    #[test]
+    fn loom_slot_load_swap() {
+        loom::model(|| {
+            let old = GCArc::new(Leaf::new());
+            let old_dropped = old.as_ref().0.clone();
+            let old_weak = old.as_weak();
+            let old_ptr = old.as_wrapper_ptr();
+            let slot = Arc::new(GCSlot::new(old));
+            let reader = {
+                let slot = slot.clone();
+                thread::spawn(move || slot.load())
+            };
+            // `old_weak` 使分配在模型中保持有效，协议出错时读者只会把已归零的强引用计数加回来，而不是访问已释放的内存
+            let returned = slot.swap(GCArc::new(Leaf::new()));
+            drop(returned);
+            let loaded = reader.join().unwrap();
+            if loaded.as_wrapper_ptr() == old_ptr {
+                // 读者读到了旧指针：`swap` 必须等它增加引用计数之后才交还旧引用，因此对象从未被释放
+                assert!(!old_dropped.load(std::sync::atomic::Ordering::Relaxed));
+                assert_eq!(loaded.strong_ref(), 1);
+            } else {
+                assert!(old_dropped.load(std::sync::atomic::Ordering::Relaxed));
+                assert!(!old_weak.is_valid());
+                assert_eq!(loaded.strong_ref(), 2);
+            }
+        });
+    }
+
+    #[test]
+    fn loom_slot_concurrent_loads() {
+        loom::model(|| {
+            let first = Leaf::new();
+            let first_dropped = first.0.clone();
+            let slot = Arc::new(GCSlot::new(GCArc::new(first)));
+            let readers: Vec<_> = (0..2)
+                .map(|_| {
+                    let slot = slot.clone();
+                    thread::spawn(move || drop(slot.load()))
+                })
+                .collect();
+            slot.store(GCArc::new(Leaf::new()));
+            for reader in readers {
+                reader.join().unwrap();
+            }
+            assert!(first_dropped.load(std::sync::atomic::Ordering::Relaxed));
+            assert_eq!(slot.load().strong_ref(), 2);
+        });
+    }
+}
@@ -0,0 +1,139 @@
+//! 用于测量回收器性能的对象图构造工具，需要启用 `bench-utils` feature
+//!
+//! 为自己的对象类型实现 `GraphNode` 后，即可用这里的构造函数生成典型形状的对象图，
+//! 再配合任意基准测试框架测量 `GC::collect` 等操作的耗时。`SimpleNode` 是一个现成的实现。
+
+use std::{collections::VecDeque, sync::Mutex};
+
+use crate::{
+    arc::{GCArc, GCArcWeak},
+    gc::GC,
+    traceable::GCTraceable,
+};
+
+/// 可由构造函数创建并连接的图节点
+pub trait GraphNode: GCTraceable<Self> + Sized + 'static {
+    /// 创建一个没有任何出边的新节点
+    fn new_node() -> Self;
+
+    /// 添加一条指向 `to` 的边，节点是共享的，因此实现需要借助内部可变性
+    fn add_edge(&self, to: &GCArc<Self>);
+}
+
+/// 只持有出边的简单节点
+pub struct SimpleNode {
+    edges: Mutex<Vec<GCArcWeak<SimpleNode>>>,
+}
+
+impl GCTraceable<SimpleNode> for SimpleNode {
+    fn collect(&self, queue: &mut VecDeque<GCArcWeak<SimpleNode>>) {
+        if let Ok(edges) = self.edges.lock() {
+            queue.extend(edges.iter().cloned());
+        }
+    }
+}
+
+impl GraphNode for SimpleNode {
+    fn new_node() -> Self {
+        SimpleNode {
+            edges: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn add_edge(&self, to: &GCArc<Self>) {
+        self.edges.lock().unwrap().push(to.as_weak());
+    }
+}
+
+/// 构造长度为 `n` 的链并加入 `gc`，返回链头。持有链头时整条链存活，丢弃后整条链成为垃圾
+/// `n` 为0时panic
+pub fn build_linear<T: GraphNode>(gc: &mut GC<T>, n: usize) -> GCArc<T> {
+    build_chain(gc, n).0
+}
+
+/// 构造长度为 `n` 的环并加入 `gc`，返回环上的一个节点。丢弃返回值后整个环成为只能由回收器释放的循环垃圾
+/// `n` 为0时panic
+pub fn build_cyclic<T: GraphNode>(gc: &mut GC<T>, n: usize) -> GCArc<T> {
+    let (head, tail) = build_chain(gc, n);
+    tail.as_ref().add_edge(&head);
+    head
+}
+
+/// 构造长度为 `n`（至少为1）的链，返回链头和链尾
+fn build_chain<T: GraphNode>(gc: &mut GC<T>, n: usize) -> (GCArc<T>, GCArc<T>) {
+    assert!(n > 0, "chain needs at least one node");
+    let head = gc.create(T::new_node());
+    let mut tail = head.clone();
+    for _ in 1..n {
+        let node = gc.create(T::new_node());
+        tail.as_ref().add_edge(&node);
+        tail = node;
+    }
+    (head, tail)
+}
+
+/// 构造 `n` 个节点和 `edges` 条随机边的图并加入 `gc`，返回0号节点
+/// 只有从0号节点可达的节点在持有返回值时存活，其余节点成为垃圾；相同的 `seed` 总是生成相同的图。`n` 为0时panic
+pub fn build_random_graph<T: GraphNode>(
+    gc: &mut GC<T>,
+    n: usize,
+    edges: usize,
+    seed: u64,
+) -> GCArc<T> {
+    assert!(n > 0, "random graph needs at least one node");
+    let nodes: Vec<GCArc<T>> = (0..n).map(|_| gc.create(T::new_node())).collect();
+
+    // xorshift64*，避免为基准测试工具引入随机数依赖
+    let mut state = seed.max(1);
+    let mut next_index = || {
+        state ^= state >> 12;
+        state ^= state << 25;
+        state ^= state >> 27;
+        (state.wrapping_mul(0x2545_f491_4f6c_dd1d) % n as u64) as usize
+    };
+    for _ in 0..edges {
+        let from = next_index();
+        let to = next_index();
+        nodes[from].as_ref().add_edge(&nodes[to]);
+    }
+    nodes[0].clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_builders() {
+        let mut gc: GC<SimpleNode> = GC::new();
+        gc.set_auto_collect(false);
+
+        let head = build_linear(&mut gc, 100);
+        gc.collect();
+        assert_eq!(gc.object_count(), 100);
+        drop(head);
+        gc.collect();
+        assert_eq!(gc.object_count(), 0);
+
+        let ring = build_cyclic(&mut gc, 50).as_weak();
+        assert!(ring.is_valid());
+        gc.collect();
+        assert!(!ring.is_valid());
+        assert_eq!(gc.object_count(), 0);
+
+        let root = build_random_graph(&mut gc, 200, 400, 7);
+        gc.collect();
+        let survivors = gc.object_count();
+        assert!((1..=200).contains(&survivors));
+        drop(root);
+        gc.collect();
+        assert_eq!(gc.object_count(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "chain needs at least one node")]
+    fn test_build_linear_empty() {
+        let mut gc: GC<SimpleNode> = GC::new();
+        let _ = build_linear(&mut gc, 0);
+    }
+}
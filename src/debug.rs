@@ -0,0 +1,160 @@
+//! 调试用的引用计数监控，需要启用 `debug` feature
+//!
+//! 注册的钩子会在 `GCArc` 的强引用计数发生变化（克隆、升级弱引用、释放）时被调用，
+//! 参数为对象id（见 `GCArc::id`）和变化后的强引用计数。钩子中可以记录
+//! `std::backtrace::Backtrace` 来定位未被释放的克隆。
+//!
+//! 启用 `track_weak_sources` 后，每个通过 `GCArc::as_weak` 创建或克隆得到的弱引用都会登记其创建位置，
+//! 可以通过 `GCArc::weak_sources` 查询当前有哪些弱引用指向某个对象。
+
+use std::{
+    backtrace::Backtrace,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, RwLock,
+    },
+};
+
+use rustc_hash::FxHashMap;
+
+type RefcountHook = Box<dyn Fn(u64, usize) + Send + Sync>;
+
+static REFCOUNT_HOOK: RwLock<Option<RefcountHook>> = RwLock::new(None);
+
+/// 设置全局引用计数钩子，替换之前设置的钩子
+/// 钩子在所有线程中被调用，不应在其中克隆或释放 `GCArc`
+pub fn set_refcount_hook<F>(hook: F)
+where
+    F: Fn(u64, usize) + Send + Sync + 'static,
+{
+    *REFCOUNT_HOOK.write().unwrap() = Some(Box::new(hook));
+}
+
+/// 移除全局引用计数钩子
+pub fn clear_refcount_hook() {
+    *REFCOUNT_HOOK.write().unwrap() = None;
+}
+
+pub(crate) fn notify_refcount(id: u64, count: usize) {
+    if let Some(hook) = REFCOUNT_HOOK.read().unwrap().as_ref() {
+        hook(id, count);
+    }
+}
+
+/// 弱引用登记表：登记编号 -> (对象id, 创建位置)，为 `None` 时不登记
+static WEAK_SOURCES: Mutex<Option<FxHashMap<u64, (u64, Backtrace)>>> = Mutex::new(None);
+static NEXT_WEAK_SOURCE: AtomicU64 = AtomicU64::new(1);
+
+/// 开启或关闭弱引用来源的登记，关闭时清空已有的登记
+/// 每次登记都会捕获调用栈，开销很大，仅用于排查对象为何被意外地保留
+/// 只登记开启之后通过 `GCArc::as_weak` 创建的弱引用及其克隆
+pub fn track_weak_sources(enabled: bool) {
+    *WEAK_SOURCES.lock().unwrap() = enabled.then(FxHashMap::default);
+}
+
+pub(crate) fn record_weak_source(object: u64) -> u64 {
+    let mut sources = WEAK_SOURCES.lock().unwrap();
+    let Some(sources) = sources.as_mut() else {
+        return 0;
+    };
+    let source = NEXT_WEAK_SOURCE.fetch_add(1, Ordering::Relaxed);
+    sources.insert(source, (object, Backtrace::force_capture()));
+    source
+}
+
+/// 克隆已登记的弱引用时登记一个新的来源，未登记的弱引用的克隆同样不登记
+pub(crate) fn record_cloned_weak_source(source: u64) -> u64 {
+    if source == 0 {
+        return 0;
+    }
+    let object = match WEAK_SOURCES.lock().unwrap().as_ref() {
+        Some(sources) => match sources.get(&source) {
+            Some((object, _)) => *object,
+            None => return 0,
+        },
+        None => return 0,
+    };
+    record_weak_source(object)
+}
+
+pub(crate) fn forget_weak_source(source: u64) {
+    if source == 0 {
+        return;
+    }
+    if let Some(sources) = WEAK_SOURCES.lock().unwrap().as_mut() {
+        sources.remove(&source);
+    }
+}
+
+pub(crate) fn weak_sources(object: u64) -> Vec<String> {
+    let sources = WEAK_SOURCES.lock().unwrap();
+    let Some(sources) = sources.as_ref() else {
+        return Vec::new();
+    };
+    let mut found: Vec<_> = sources
+        .iter()
+        .filter(|(_, (id, _))| *id == object)
+        .collect();
+    found.sort_by_key(|(source, _)| **source);
+    found
+        .into_iter()
+        .map(|(_, (_, backtrace))| backtrace.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::VecDeque,
+        sync::{Arc, Mutex},
+    };
+
+    use crate::arc::{GCArc, GCArcWeak};
+    use crate::traceable::GCTraceable;
+
+    struct Leaf;
+
+    impl GCTraceable<Leaf> for Leaf {
+        fn collect(&self, _queue: &mut VecDeque<GCArcWeak<Leaf>>) {}
+    }
+
+    #[test]
+    fn test_refcount_hook() {
+        let obj = GCArc::new(Leaf);
+        let id = obj.id();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+        super::set_refcount_hook(move |object, count| {
+            if object == id {
+                sink.lock().unwrap().push(count);
+            }
+        });
+
+        let cloned = obj.clone();
+        let upgraded = obj.as_weak().upgrade().unwrap();
+        drop(cloned);
+        drop(upgraded);
+        super::clear_refcount_hook();
+        drop(obj);
+
+        assert_eq!(*events.lock().unwrap(), vec![2, 3, 2, 1]);
+    }
+
+    #[test]
+    fn test_weak_sources() {
+        let obj = GCArc::new(Leaf);
+        let untracked = obj.as_weak();
+        super::track_weak_sources(true);
+        let first = obj.as_weak();
+        let second = first.clone();
+        let _ = untracked.clone();
+        assert_eq!(obj.weak_sources().len(), 2);
+        drop(first);
+        assert_eq!(obj.weak_sources().len(), 1);
+        assert!(obj.weak_sources()[0].contains("test_weak_sources"));
+        super::track_weak_sources(false);
+        assert!(obj.weak_sources().is_empty());
+        drop(second);
+        drop(untracked);
+    }
+}
@@ -0,0 +1,133 @@
+//! 基于纪元（epoch）回收的强引用槽，需要启用 `epoch` feature
+//!
+//! `EpochSlot` 持有一个强引用。读取方在固定（pin）当前纪元后借用槽中的 `GCArc`，
+//! 不克隆、也不升级弱引用，因此读多写少的热路径上没有对引用计数的原子读-改-写操作。
+//! 写入方替换槽中的引用后，旧引用的释放被推迟到所有可能仍在借用它的读取方离开纪元之后，
+//! 由 `crossbeam-epoch` 在纪元边界批量完成。
+//!
+//! 被推迟释放的旧引用在真正释放之前仍然计入强引用计数，对象在此期间被视为根对象。
+
+use std::sync::atomic::Ordering;
+
+use crossbeam_epoch::{Atomic, Owned};
+
+pub use crossbeam_epoch::{pin, Guard};
+
+use crate::{arc::GCArc, traceable::GCTraceable};
+
+/// 读取方无需修改引用计数即可访问的强引用槽
+pub struct EpochSlot<T: GCTraceable<T> + 'static> {
+    value: Atomic<GCArc<T>>,
+}
+
+impl<T> EpochSlot<T>
+where
+    T: GCTraceable<T> + Send + Sync + 'static,
+{
+    pub fn new(value: GCArc<T>) -> Self {
+        Self {
+            value: Atomic::new(value),
+        }
+    }
+
+    /// 在 `guard` 所固定的纪元内借用槽中的引用，不修改引用计数
+    /// 同一个 `guard` 可以用于多次读取，以摊薄固定纪元的开销
+    pub fn load<'g>(&self, guard: &'g Guard) -> &'g GCArc<T> {
+        // `Acquire`：与 `store` 中的 `Release` 配对，保证读到的新值已完整初始化
+        let shared = self.value.load(Ordering::Acquire, guard);
+        // SAFETY: 槽中的指针总是非空的；被替换的旧值只会在 `guard` 离开纪元之后才被释放
+        unsafe { shared.deref() }
+    }
+
+    /// 固定当前纪元并借用槽中的引用调用 `f`
+    pub fn read<R>(&self, f: impl FnOnce(&GCArc<T>) -> R) -> R {
+        let guard = pin();
+        f(self.load(&guard))
+    }
+
+    /// 获取槽中当前引用的一个新的强引用，用于需要在纪元之外继续持有对象的场景
+    pub fn load_arc(&self) -> GCArc<T> {
+        self.read(GCArc::clone)
+    }
+
+    /// 将槽中的引用替换为 `new`，旧引用在所有可能仍在借用它的读取方离开纪元之后才被释放
+    pub fn store(&self, new: GCArc<T>) {
+        let guard = pin();
+        // `AcqRel`：`Release` 发布新值，`Acquire` 保证推迟释放的旧值对本线程可见
+        let old = self.value.swap(Owned::new(new), Ordering::AcqRel, &guard);
+        // SAFETY: 旧值已从槽中移除，之后进入纪元的读取方无法再读到它；
+        // `GCArc<T>` 在 `T: Send + Sync` 时可以在其他线程中释放
+        unsafe { guard.defer_destroy(old) };
+    }
+}
+
+impl<T> Drop for EpochSlot<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn drop(&mut self) {
+        // SAFETY: `&mut self` 保证不会再有读取方借用槽中的值
+        unsafe {
+            let guard = crossbeam_epoch::unprotected();
+            drop(self.value.load(Ordering::Relaxed, guard).into_owned());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+    use crate::{
+        arc::{GCArcWeak, GCRef},
+        gc::GC,
+    };
+
+    struct ValueObject(i32);
+
+    impl GCTraceable<ValueObject> for ValueObject {
+        fn collect(&self, _queue: &mut VecDeque<GCArcWeak<ValueObject>>) {}
+    }
+
+    #[test]
+    fn test_epoch_slot() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let first = gc.create(ValueObject(1));
+        let slot = EpochSlot::new(first.clone());
+
+        // 借用读取不改变引用计数
+        let guard = pin();
+        let before = first.strong_ref();
+        assert_eq!(slot.load(&guard).as_ref().0, 1);
+        assert_eq!(first.strong_ref(), before);
+        drop(guard);
+
+        // 槽持有的引用使新对象作为根对象存活
+        drop(first);
+        slot.store(gc.create(ValueObject(2)));
+        assert_eq!(slot.read(|arc| arc.as_ref().0), 2);
+        gc.collect();
+        assert_eq!(slot.load_arc().as_ref().0, 2);
+        assert!(slot.load_arc().external_strong_count() > 0);
+
+        std::thread::scope(|s| {
+            for i in 0..4 {
+                let slot = &slot;
+                s.spawn(move || {
+                    for j in 0..1000 {
+                        if j % 10 == 0 {
+                            slot.store(GCArc::new(ValueObject(i)));
+                        } else {
+                            assert!(slot.read(|arc| arc.as_ref().0) <= 3);
+                        }
+                    }
+                });
+            }
+        });
+        let last = slot.load_arc();
+        drop(slot);
+        assert_eq!(last.strong_ref(), 1);
+    }
+}
@@ -0,0 +1,63 @@
+use std::fmt;
+
+/// 垃圾回收器操作可能产生的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GCError {
+    /// 即使在强制回收之后，添加对象仍会超过硬性内存上限
+    OutOfMemory {
+        /// 添加对象后将会占用的内存估算值（字节）
+        requested: usize,
+        /// 硬性内存上限（字节）
+        limit: usize,
+    },
+}
+
+impl fmt::Display for GCError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GCError::OutOfMemory { requested, limit } => write!(
+                f,
+                "GC hard memory limit exceeded: {} bytes requested, limit is {} bytes",
+                requested, limit
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GCError {}
+
+/// 类型擦除对象的向下转换失败：对象的实际类型与请求的类型不一致
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DowncastError {
+    /// 请求转换到的类型名
+    pub requested: &'static str,
+    /// 对象实际存储的类型名
+    pub actual: &'static str,
+}
+
+impl fmt::Display for DowncastError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot downcast GC object of type `{}` to `{}`",
+            self.actual, self.requested
+        )
+    }
+}
+
+impl std::error::Error for DowncastError {}
+
+/// 弱引用升级失败：对象已被释放
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WeakUpgradeError;
+
+impl fmt::Display for WeakUpgradeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot upgrade weak reference: the GC object has been freed"
+        )
+    }
+}
+
+impl std::error::Error for WeakUpgradeError {}
@@ -1,21 +1,300 @@
 use std::{
-    collections::VecDeque,
-    sync::{atomic::AtomicUsize, Mutex},
+    any::{Any, TypeId},
+    cell::RefCell,
+    collections::{BTreeMap, HashMap, VecDeque},
+    hash::Hash,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize},
+        mpsc::{SyncSender, TrySendError},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
 };
 
 use rustc_hash::FxHashMap;
 
 use crate::{
-    arc::{GCArc, GCRef},
-    traceable::GCTraceable,
+    arc::{GCArc, GCArcWeak, GCRef},
+    error::GCError,
+    traceable::{trace_children, Edge, GCTraceable},
 };
 
+/// 流水线回收中每批交给后台线程释放的对象数
+const PIPELINED_SWEEP_BATCH: usize = 4096;
+
 pub struct GC<T: GCTraceable<T> + 'static> {
     gc_refs: Mutex<Vec<GCArc<T>>>,
+    dirty: AtomicBool, // 追踪列表自最近一次被确认为空以来是否被attach过，为 `false` 时追踪列表一定为空
     attach_count: AtomicUsize,
     collection_percentage: usize, // 百分比阈值，如20表示20%
     memory_threshold: Option<usize>, // 内存阈值（字节），达到此值时触发回收
+    hard_limit: Option<usize>, // 硬性内存上限（字节），`try_attach` 在回收后仍会超过此值时失败
     allocated_memory: AtomicUsize, // 当前分配的内存大小估算
+    total_allocated: AtomicUsize, // 累计通过此GC分配的内存大小估算，只增不减
+    auto_collect: bool, // 是否允许attach时自动触发回收
+    weak_refs: Mutex<Vec<(GCArcWeak<T>, u64)>>, // 弱引用追踪模式下追踪的对象及其id
+    weak_tracking: bool, // 是否为弱引用追踪模式，此模式下GC不持有对象的强引用
+    last_collection_stats: CollectionStats, // 最近一次回收的统计信息
+    frozen: FxHashMap<usize, FrozenSubgraph<T>>, // 以根对象地址为键的冻结子图
+    drop_panics: Mutex<Vec<Box<dyn Any + Send>>>, // 回收过程中对象 `Drop` 产生的panic
+    grace_period: u32, // 新attach的对象在成为可回收对象前需要存活的回收次数
+    young: Mutex<FxHashMap<u64, u32>>, // 处于宽限期的对象（以对象id为键）及剩余的回收次数
+    post_sweep_callbacks: Mutex<Vec<PostSweepCallback>>, // 每次回收结束后调用的回调
+    progress: Arc<CollectionProgress>, // 正在进行的回收的进度计数器
+    growth_increment: Option<usize>, // 追踪列表已满时每次扩容的固定增量，`None` 表示使用 `Vec` 默认的倍增策略
+    pause_depth: usize, // `pause` 的嵌套层数，大于0时不进行自动回收
+    soft_refs: Mutex<Vec<(GCArcWeak<T>, std::sync::Weak<()>)>>, // 软引用的目标及软引用存活标记
+    min_interval: Option<Duration>, // 两次自动回收之间的最短间隔
+    max_interval: Option<Duration>, // 两次自动回收之间的最长间隔，超过后下一次attach必定触发回收
+    last_collection: Instant, // 最近一次回收结束（或GC创建）的时刻
+    large_object_threshold: Option<usize>, // 大对象阈值（字节），`size_hint` 超过此值的对象进入大对象空间
+    large: Mutex<rustc_hash::FxHashSet<usize>>, // 大对象空间中的对象（以 `GCWrapper` 地址为键）
+    outstanding_get_all: Arc<AtomicUsize>, // 尚未被丢弃的 `get_all_tracked` 结果数
+    min_garbage: Option<usize>, // 自动回收前至少需要的新attach次数，作为可回收垃圾量的估算
+    null_dead_weaks: bool, // 回收后是否让存活对象丢弃指向已释放对象的弱引用边
+    liveness: Mutex<FxHashMap<u64, LivenessPredicate>>, // 以对象id为键的存活条件，条件成立时对象是根对象
+    adaptive: bool, // 是否根据回收效果自动调整 `collection_percentage`
+    garbage_ratio: f64, // 近期回收中被回收对象所占比例的指数加权移动平均
+    event_sink: Option<SyncSender<CollectionEvent>>, // 接收回收事件的通道，见 `set_event_sink`
+}
+
+/// 清除后回调，参数为本次被回收对象的id
+type PostSweepCallback = Box<dyn FnMut(&[u64]) + Send>;
+
+/// 存活条件，见 `GC::keep_while`
+type LivenessPredicate = Box<dyn Fn() -> bool + Send>;
+
+/// 被冻结的子图：根对象在冻结时可达的所有对象
+/// 持有成员的弱引用以保证其地址在解冻前不会被复用
+/// 其中第一个成员为根对象
+struct FrozenSubgraph<T: GCTraceable<T> + 'static> {
+    members: Vec<GCArcWeak<T>>,
+    member_keys: Vec<usize>,
+}
+
+/// 标记队列中的对象
+enum Pending<T: GCTraceable<T> + 'static> {
+    /// 被追踪的对象，以值的地址表示。标记期间追踪列表被锁定并持有对象的强引用，无需弱引用即可访问
+    Tracked(usize),
+    /// 未被追踪、经由其他对象到达的对象，持有弱引用以防止其地址在出队前被复用
+    Untracked(GCArcWeak<T>),
+}
+
+impl<T> Pending<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    /// 由弱引用构造队列项：`marked` 中存在的对象是被追踪的对象，弱引用随即被丢弃
+    fn new(weak: GCArcWeak<T>, marked: &FxHashMap<usize, bool>) -> Self {
+        let key = weak.as_wrapper_ptr() as usize;
+        if marked.contains_key(&key) {
+            Pending::Tracked(key)
+        } else {
+            Pending::Untracked(weak)
+        }
+    }
+
+    /// 由 `mark_roots` 返回的根对象下标构造初始队列
+    fn from_roots(refs: &[GCArc<T>], roots: &[usize]) -> VecDeque<Self> {
+        roots
+            .iter()
+            .map(|&index| Pending::Tracked(refs[index].as_ref() as *const T as usize))
+            .collect()
+    }
+
+    /// 对象的地址，与 `marked` 的键相同
+    fn key(&self) -> usize {
+        match self {
+            Pending::Tracked(key) => *key,
+            Pending::Untracked(weak) => weak.as_wrapper_ptr() as usize,
+        }
+    }
+}
+
+/// 释放时仍被其他地方引用的被回收对象，见 `GC::release_batch`
+struct Survivor<T: GCTraceable<T> + 'static> {
+    weak: GCArcWeak<T>,
+    /// 释放前记录的外部持有者数，不含GC和其他被回收对象的强引用边
+    outside: usize,
+}
+
+/// `GC::with_config` 使用的配置，`Default` 与 `GC::new` 的默认值相同
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GCConfig {
+    /// 百分比阈值，attach次数超过当前对象数的此百分比时触发回收
+    pub collection_percentage: usize,
+    /// 内存阈值（字节），分配的内存超过此值时触发回收，None表示不使用
+    pub memory_threshold: Option<usize>,
+    /// 追踪列表的初始容量
+    pub initial_capacity: usize,
+    /// 是否允许attach时自动触发回收
+    pub auto_collect: bool,
+    /// 两次自动回收之间的最短间隔，None表示不限制
+    pub min_interval: Option<Duration>,
+}
+
+impl Default for GCConfig {
+    fn default() -> Self {
+        Self {
+            collection_percentage: 20,
+            memory_threshold: None,
+            initial_capacity: 0,
+            auto_collect: true,
+            min_interval: None,
+        }
+    }
+}
+
+/// 一次回收过程的统计信息
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CollectionStats {
+    /// 标记阶段中 BFS 队列的最大长度
+    pub max_queue_len: usize,
+    /// 标记阶段中被标记对象距根对象的最大深度（根对象深度为1）
+    pub max_depth: usize,
+    /// 回收开始时仍未被丢弃的 `GC::get_all_tracked` 结果数，其中的对象都被视为根对象，非零时回收结果可能出乎意料
+    pub outstanding_get_all: usize,
+}
+
+/// 通过 `GC::set_event_sink` 设置的通道接收的回收事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollectionEvent {
+    /// 一次回收开始
+    CollectionStarted,
+    /// 一个对象被回收，`bytes` 为回收器对其内存占用的估算
+    ObjectFreed { id: u64, bytes: usize },
+    /// 一次回收结束，附带本次回收的统计信息
+    CollectionFinished(CollectionStats),
+}
+
+/// 软引用，通过 `GC::soft_ref` 创建，适用于缓存
+/// 与强引用（总是保持对象存活）和弱引用（从不保持对象存活）不同，软引用的目标在普通回收中被视为根对象，
+/// 只有在回收开始时内存估算超过内存阈值（内存压力）的情况下才不再保持目标存活，此时目标可能被回收，软引用随之失效。
+/// 软引用被丢弃后不再影响目标的存活
+pub struct GCSoftRef<T: GCTraceable<T> + 'static> {
+    target: GCArcWeak<T>,
+    _alive: Arc<()>,
+}
+
+impl<T> GCSoftRef<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    /// 获取目标的强引用，目标已被回收时返回 `None`
+    #[must_use]
+    pub fn upgrade(&self) -> Option<GCArc<T>> {
+        self.target.upgrade()
+    }
+
+    /// 目标是否仍然存活
+    pub fn is_valid(&self) -> bool {
+        self.target.is_valid()
+    }
+}
+
+/// `GC::collect_explained` 报告的单个对象在一次回收中的结局
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectFate {
+    /// 作为根对象保留：存在外部强引用或处于宽限期
+    KeptRoot,
+    /// 从根对象可达而被保留
+    KeptReachable,
+    /// 被固定而被保留
+    KeptPinned,
+    /// 被回收
+    Freed,
+}
+
+/// 某一时刻所有被追踪对象的引用计数快照，通过 `GC::snapshot` 获取
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeapSnapshot {
+    /// 以对象id为键的 `(强引用计数, 弱引用计数)`
+    pub counts: BTreeMap<u64, (usize, usize)>,
+}
+
+/// 同一对象在两个快照之间的引用计数变化
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RefcountDelta {
+    pub id: u64,
+    pub strong_delta: isize,
+    pub weak_delta: isize,
+}
+
+impl HeapSnapshot {
+    /// 与更晚的快照 `other` 比较，返回同时出现在两个快照中且引用计数发生变化的对象，按id升序排列
+    /// 在两次快照之间持续增长的计数通常意味着引用泄漏
+    pub fn diff(&self, other: &HeapSnapshot) -> Vec<RefcountDelta> {
+        self.counts
+            .iter()
+            .filter_map(|(&id, &(strong, weak))| {
+                let &(new_strong, new_weak) = other.counts.get(&id)?;
+                let delta = RefcountDelta {
+                    id,
+                    strong_delta: new_strong as isize - strong as isize,
+                    weak_delta: new_weak as isize - weak as isize,
+                };
+                (delta.strong_delta != 0 || delta.weak_delta != 0).then_some(delta)
+            })
+            .collect()
+    }
+}
+
+/// GC启发式回收所用计数器的快照，用于在保存和恢复程序状态时保持回收时机，
+/// 通过 `GC::export_state` 和 `GC::import_state` 使用。不包含对象本身
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GCState {
+    /// 自上次回收以来的attach次数
+    pub attach_count: usize,
+    /// 当前分配的内存大小估算（字节）。`import_state` 不导入此项，它始终由实际被追踪的对象决定
+    pub allocated_memory: usize,
+    /// 累计分配的内存大小估算（字节）
+    pub total_allocated: usize,
+}
+
+/// 回收进度计数器，回收过程中持续更新，可以在其他线程中轮询
+/// 通过 `GC::progress_handle` 获取共享句柄
+#[derive(Debug, Default)]
+pub struct CollectionProgress {
+    in_progress: AtomicBool,
+    marked_so_far: AtomicUsize,
+    swept_so_far: AtomicUsize,
+}
+
+impl CollectionProgress {
+    /// 返回当前回收已标记和已清除的对象数，没有正在进行的回收时返回 `None`
+    pub fn get(&self) -> Option<(usize, usize)> {
+        if !self.in_progress.load(std::sync::atomic::Ordering::Acquire) {
+            return None;
+        }
+        Some((
+            self.marked_so_far.load(std::sync::atomic::Ordering::Relaxed),
+            self.swept_so_far.load(std::sync::atomic::Ordering::Relaxed),
+        ))
+    }
+
+    fn begin(&self) {
+        self.marked_so_far
+            .store(0, std::sync::atomic::Ordering::Relaxed);
+        self.swept_so_far
+            .store(0, std::sync::atomic::Ordering::Relaxed);
+        self.in_progress
+            .store(true, std::sync::atomic::Ordering::Release);
+    }
+
+    fn end(&self) {
+        self.in_progress
+            .store(false, std::sync::atomic::Ordering::Release);
+    }
+}
+
+impl<T> Default for GC<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[allow(dead_code)]
@@ -24,78 +303,275 @@ where
     T: GCTraceable<T> + 'static,
 {    /// 创建一个新的垃圾回收器，默认回收触发百分比为20%
     pub fn new() -> Self {
+        Self::with_config(GCConfig::default())
+    }
+
+    /// 以配置结构体创建垃圾回收器，一次设置所有常用参数，其余参数为默认值
+    pub fn with_config(config: GCConfig) -> Self {
         Self {
-            gc_refs: Mutex::new(Vec::new()),
+            gc_refs: Mutex::new(Vec::with_capacity(config.initial_capacity)),
+            dirty: AtomicBool::new(false),
             attach_count: AtomicUsize::new(0),
-            collection_percentage: 20, // 默认20%增长时触发回收
-            memory_threshold: None, // 默认不使用内存阈值
+            collection_percentage: config.collection_percentage,
+            memory_threshold: config.memory_threshold,
+            hard_limit: None,
             allocated_memory: AtomicUsize::new(0),
+            total_allocated: AtomicUsize::new(0),
+            auto_collect: config.auto_collect,
+            weak_refs: Mutex::new(Vec::new()),
+            weak_tracking: false,
+            last_collection_stats: CollectionStats::default(),
+            frozen: FxHashMap::default(),
+            drop_panics: Mutex::new(Vec::new()),
+            grace_period: 0,
+            young: Mutex::new(FxHashMap::default()),
+            post_sweep_callbacks: Mutex::new(Vec::new()),
+            progress: Arc::new(CollectionProgress::default()),
+            growth_increment: None,
+            pause_depth: 0,
+            soft_refs: Mutex::new(Vec::new()),
+            min_interval: config.min_interval,
+            max_interval: None,
+            last_collection: Instant::now(),
+            large_object_threshold: None,
+            large: Mutex::new(rustc_hash::FxHashSet::default()),
+            outstanding_get_all: Arc::new(AtomicUsize::new(0)),
+            min_garbage: None,
+            null_dead_weaks: false,
+            liveness: Mutex::new(FxHashMap::default()),
+            adaptive: false,
+            garbage_ratio: 0.0,
+            event_sink: None,
         }
-    }    /// 创建一个新的垃圾回收器，指定回收触发的百分比
+    }
+
+    /// 创建一个新的垃圾回收器，指定回收触发的百分比
     /// 例如，`new_with_percentage(30)`表示当attach次数超过当前对象数的30%时触发回收
     pub fn new_with_percentage(percentage: usize) -> Self {
-        Self {
-            gc_refs: Mutex::new(Vec::new()),
-            attach_count: AtomicUsize::new(0),
+        Self::with_config(GCConfig {
             collection_percentage: percentage,
-            memory_threshold: None, // 默认不使用内存阈值
-            allocated_memory: AtomicUsize::new(0),
-        }
+            ..GCConfig::default()
+        })
     }
 
     /// 创建一个新的垃圾回收器，指定内存阈值（字节）
     /// 当分配的内存超过指定阈值时触发回收
     pub fn new_with_memory_threshold(memory_threshold: usize) -> Self {
-        Self {
-            gc_refs: Mutex::new(Vec::new()),
-            attach_count: AtomicUsize::new(0),
-            collection_percentage: 20, // 保持默认百分比作为备用触发条件
-            memory_threshold: Some(memory_threshold),
-            allocated_memory: AtomicUsize::new(0),
-        }
+        Self::with_config(GCConfig {
+            memory_threshold: Some(memory_threshold), // 保持默认百分比作为备用触发条件
+            ..GCConfig::default()
+        })
     }
 
     /// 创建一个新的垃圾回收器，同时指定百分比阈值和内存阈值
     /// 任一条件满足时都会触发回收
     pub fn new_with_thresholds(percentage: usize, memory_threshold: usize) -> Self {
-        Self {
-            gc_refs: Mutex::new(Vec::new()),
-            attach_count: AtomicUsize::new(0),
+        Self::with_config(GCConfig {
             collection_percentage: percentage,
             memory_threshold: Some(memory_threshold),
-            allocated_memory: AtomicUsize::new(0),
-        }
-    }    pub fn attach(&mut self, gc_arc: &GCArc<T>) {
-        {
-            let mut gc_refs = self.gc_refs.lock().unwrap();
-            gc_refs.push(gc_arc.clone());
+            ..GCConfig::default()
+        })
+    }
+
+    /// 创建一个弱引用追踪模式的垃圾回收器
+    /// 此模式下 `attach` 只保存对象的弱引用，GC本身不会延长对象的生命周期，
+    /// 对象的存活完全由外部强引用决定；回收时清除所有已经失效的追踪项
+    pub fn new_weak_tracking() -> Self {
+        let mut gc = Self::new();
+        gc.weak_tracking = true;
+        gc
+    }
+
+    /// 是否为弱引用追踪模式
+    pub fn is_weak_tracking(&self) -> bool {
+        self.weak_tracking
+    }
+
+    /// 将对象加入GC追踪，之后可能触发一次启发式回收
+    ///
+    /// 对象的生命周期：`attach` 使GC持有一个强引用并将 `attached_gc_count` 加1；
+    /// `detach` 释放该强引用并将计数减1，此后对象仅由外部引用维持，最后一个外部引用释放时立即被释放。
+    /// 被 `detach` 的对象可以再次 `attach`，重新成为可回收对象：由于调用者必须持有存活的 `GCArc`，
+    /// 已被释放的对象不可能被重新添加，且调用者持有的引用保证对象在本次可能触发的回收中作为根对象存活。
+    ///
+    /// `attach` 不检查硬性内存上限（`set_hard_limit`），总是会添加对象；为了保持现有调用方式不变，
+    /// 它没有改为返回 `Result`。需要在超过上限时失败的调用者应使用 `try_attach`
+    pub fn attach(&mut self, gc_arc: &GCArc<T>) {
+        self.attach_unchecked(gc_arc);
+
+        // 启发式回收检查
+        if self.auto_collect && self.should_collect() {
+            self.collect();
         }
+    }
 
-        self.attach_count
-            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    /// 添加对象，若添加后内存估算会超过硬性内存上限，则先强制进行一次回收，
+    /// 回收后仍会超过上限时返回 `GCError::OutOfMemory` 且不添加对象
+    pub fn try_attach(&mut self, gc_arc: &GCArc<T>) -> Result<(), GCError> {
+        if let Some(limit) = self.hard_limit {
+            let obj_size = std::mem::size_of::<T>() + std::mem::size_of::<GCArc<T>>();
+            if self.allocated_memory() + obj_size > limit {
+                self.collect();
+                let requested = self.allocated_memory() + obj_size;
+                if requested > limit {
+                    return Err(GCError::OutOfMemory { requested, limit });
+                }
+            }
+        }
+        self.attach(gc_arc);
+        Ok(())
+    }
 
-        gc_arc
+    /// 若对象尚未被当前GC追踪则添加它，返回是否为新添加的对象
+    /// 与 `attach` 不同，重复调用不会导致同一对象被追踪多次
+    /// 未被任何GC追踪的对象（`attached_gc_count` 为0）直接添加，无需扫描追踪列表；
+    /// 只有对象已被某个GC追踪或处于弱引用追踪模式时才需要检查它是否属于当前GC
+    pub fn track(&mut self, gc_arc: &GCArc<T>) -> bool {
+        let attached = gc_arc
             .inner()
             .attached_gc_count
+            .load(std::sync::atomic::Ordering::Relaxed);
+        if (attached > 0 || self.weak_tracking) && self.contains(gc_arc) {
+            return false;
+        }
+        self.attach(gc_arc);
+        true
+    }
+
+    /// 对象是否正被当前GC追踪
+    pub fn contains(&self, gc_arc: &GCArc<T>) -> bool {
+        if self.weak_tracking {
+            return self
+                .weak_refs
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|(r, _)| r.as_wrapper_ptr() == gc_arc.as_wrapper_ptr());
+        }
+        self.gc_refs
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|r| GCArc::ptr_eq(r, gc_arc))
+    }
+
+    /// 批量添加对象到GC中，所有对象添加完成后只进行一次启发式回收检查
+    pub fn attach_many<I>(&mut self, gc_arcs: I)
+    where
+        I: IntoIterator<Item = GCArc<T>>,
+    {
+        for gc_arc in gc_arcs {
+            self.attach_unchecked(&gc_arc);
+        }
+
+        // 启发式回收检查
+        if self.auto_collect && self.should_collect() {
+            self.collect();
+        }
+    }
+
+    /// 追踪列表已满时按设置的固定增量扩容，未设置增量时交给 `Vec` 的默认策略
+    fn grow<E>(&self, refs: &mut Vec<E>) {
+        if let Some(increment) = self.growth_increment {
+            if refs.len() == refs.capacity() {
+                refs.reserve_exact(increment);
+            }
+        }
+    }
+
+    /// 添加对象并更新各项计数，但不进行启发式回收检查
+    fn attach_unchecked(&self, gc_arc: &GCArc<T>) {
+        if self.weak_tracking {
+            // 弱引用追踪模式下不持有强引用，也不计入 `attached_gc_count`
+            let mut weak_refs = self.weak_refs.lock().unwrap();
+            self.grow(&mut weak_refs);
+            weak_refs.push((gc_arc.as_weak(), gc_arc.id()));
+        } else {
+            // 先让GC持有强引用再增加 `attached_gc_count`，
+            // 保证任何时刻对象的强引用计数都不会低于GC持有的引用数，避免被误判为无外部引用。
+            {
+                let mut gc_refs = self.gc_refs.lock().unwrap();
+                self.grow(&mut gc_refs);
+                gc_refs.push(gc_arc.clone());
+            }
+            self.dirty.store(true, std::sync::atomic::Ordering::Relaxed);
+
+            gc_arc
+                .inner()
+                .attached_gc_count
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+
+            // 新attach的对象在宽限期内被视为根对象
+            if self.grace_period > 0 {
+                self.young
+                    .lock()
+                    .unwrap()
+                    .insert(gc_arc.id(), self.grace_period);
+            }
+
+            // 超过大对象阈值的对象进入大对象空间，只在 `collect_major` 中被回收
+            if self
+                .large_object_threshold
+                .is_some_and(|threshold| gc_arc.as_ref().size_hint() > threshold)
+            {
+                self.large
+                    .lock()
+                    .unwrap()
+                    .insert(gc_arc.as_wrapper_ptr() as usize);
+            }
+        }
+
+        self.attach_count
             .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
 
         // 更新内存估算（使用对象的大小估算）
         let obj_size = std::mem::size_of::<T>() + std::mem::size_of::<GCArc<T>>();
         self.allocated_memory
             .fetch_add(obj_size, std::sync::atomic::Ordering::Relaxed);
+        self.total_allocated
+            .fetch_add(obj_size, std::sync::atomic::Ordering::Relaxed);
+    }
 
-        // 启发式回收检查
-        if self.should_collect() {
-            self.collect();
+    /// 将对象加入GC追踪，并返回一个在被丢弃时自动 `detach` 该对象的守卫，把追踪范围限定在词法作用域内
+    /// 守卫独占借用GC，可以通过它（`Deref`/`DerefMut`）继续使用GC
+    pub fn attach_scoped(&mut self, gc_arc: &GCArc<T>) -> ScopedAttach<'_, T> {
+        self.attach(gc_arc);
+        ScopedAttach {
+            gc: self,
+            gc_arc: gc_arc.clone(),
+        }
+    }
+
+    /// 将对象移出GC追踪，返回对象此前是否被追踪
+    /// 移出后GC不再持有对象的强引用，对象可以通过 `attach` 重新加入，见 `attach` 中关于生命周期的说明
+    pub fn detach(&mut self, gc_arc: &GCArc<T>) -> bool {
+        if self.weak_tracking {
+            let mut weak_refs = self.weak_refs.lock().unwrap();
+            let Some(index) = weak_refs
+                .iter()
+                .position(|(r, _)| r.as_wrapper_ptr() == gc_arc.as_wrapper_ptr())
+            else {
+                return false;
+            };
+            weak_refs.swap_remove(index);
+
+            let obj_size = std::mem::size_of::<T>() + std::mem::size_of::<GCArc<T>>();
+            self.allocated_memory
+                .fetch_sub(obj_size, std::sync::atomic::Ordering::Relaxed);
+            return true;
         }
-    }    pub fn detach(&mut self, gc_arc: &GCArc<T>) -> bool {
+
         let mut gc_refs = self.gc_refs.lock().unwrap();
         if let Some(index) = gc_refs.iter().position(|r| GCArc::ptr_eq(r, gc_arc)) {
-            gc_refs.swap_remove(index);
-            gc_arc
-                .inner()
-                .attached_gc_count
-                .fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+            // 调用者仍持有 `gc_arc`，释放GC持有的这份强引用不会触发对象的析构
+            drop(gc_refs.swap_remove(index));
+            self.young.lock().unwrap().remove(&gc_arc.id());
+            self.large
+                .lock()
+                .unwrap()
+                .remove(&(gc_arc.as_wrapper_ptr() as usize));
+            self.liveness.lock().unwrap().remove(&gc_arc.id());
+            gc_arc.inner().release_attachment();
             
             // 更新内存估算
             let obj_size = std::mem::size_of::<T>() + std::mem::size_of::<GCArc<T>>();
@@ -107,16 +583,18 @@ where
             false
         }
     }
-    pub fn collect(&mut self) {
-        // 执行垃圾回收过程。
-        // 该过程分为两个主要阶段：标记（Mark）和清除（Sweep）。
-        // 1. 标记阶段：从根对象开始，遍历所有可达的对象，并将其标记为“存活”。
-        // 2. 清除阶段：遍历所有GC管理的对象，回收所有未被标记为“存活”的对象。
 
-        // 获取对GC管理的引用列表的可变借用。
-        // `refs` 存储了所有由GC跟踪的 GCArc<T> 对象。
-        let mut refs = self.gc_refs.lock().unwrap();
+    /// 标记阶段：从根对象开始遍历所有可达的对象，返回每个对象（以内存地址为键）是否被标记为存活
+    fn mark(&self, refs: &[GCArc<T>], stats: &mut CollectionStats) -> FxHashMap<usize, bool> {
+        let (mut marked, roots) = self.mark_roots(refs);
+        let queue = Pending::from_roots(refs, &roots);
+        self.trace(&mut marked, queue, stats);
+        marked
+    }
 
+    /// 标记阶段的第一步：为所有被追踪的对象建立初始（未标记）状态，并找出根对象
+    /// 根对象以其在 `refs` 中的下标返回，不为它们创建弱引用
+    fn mark_roots(&self, refs: &[GCArc<T>]) -> (FxHashMap<usize, bool>, Vec<usize>) {
         // 初始化一个哈希表 `marked` 用于存储每个对象的标记状态。
         // 键是对象的内存地址（usize类型），值是布尔类型（true表示已标记，false表示未标记）。
         // 使用 FxHashMap 是为了更快的哈希性能。
@@ -129,45 +607,130 @@ where
             marked.insert(r.as_ref() as *const T as usize, false);
         }
 
-        // 初始化一个双端队列 `queue`，用于广度优先搜索（BFS）遍历对象图。
-        // 队列中存储的是对象的弱引用 (GCArcWeak<T>)，以避免在遍历过程中增加强引用计数，
-        // 从而干扰对象的实际存活状态判断。
-        let mut queue = VecDeque::new();
+        // 根对象在 `refs` 中的下标。调用者持有 `refs`，无需为根对象创建弱引用或强引用，
+        // 因此判断根对象以及之后的标记都不会改变对象可观察到的引用计数。
+        let mut roots = Vec::new();
 
         // 识别根对象（Root Objects）。
         // 根对象是那些除了GC自身持有的引用外，仍然有外部强引用的对象。
         // 在这个实现中，如果一个 GCArc<T> 的强引用计数大于attached_gc_count，
         // （其中attached_gc_count个引用来自各gc的 `gc_refs` 向量，其余来自外部代码），
         // 则认为它是根对象。
-        // 将所有根对象的弱引用添加到处理队列 `queue` 中。
-        for r in refs.iter() {
-            if r.strong_ref()
-                > r.inner()
-                    .attached_gc_count
-                    .load(std::sync::atomic::Ordering::Relaxed)
+        // 被固定（pinned）的对象、处于宽限期的新对象、大对象空间中的对象以及存活条件成立的对象同样被视为根对象。
+        // 对于报告强引用边的类型，被追踪对象之间的强引用边同样计入了子对象的强引用计数，
+        // 需要统计每个对象收到的此类引用数，在判断根对象时扣除。
+        // 统计完成后立即释放边中的强引用，以免影响下面读取的引用计数。
+        let mut internal_refs: FxHashMap<usize, usize> = FxHashMap::default();
+        if T::TRACES_STRONG_EDGES {
+            let mut edges = Vec::new();
+            for r in refs.iter() {
+                r.as_ref().edges(&mut edges);
+                for edge in edges.drain(..) {
+                    if let Edge::Strong(child) = edge {
+                        *internal_refs
+                            .entry(child.as_ref() as *const T as usize)
+                            .or_default() += 1;
+                    }
+                }
+            }
+        }
+
+        // 没有内存压力时，仍存活的软引用的目标同样是根对象；顺便清理已失效的软引用。
+        let under_pressure = self
+            .memory_threshold
+            .is_some_and(|threshold| self.allocated_memory() > threshold);
+        let mut soft_roots = rustc_hash::FxHashSet::default();
+        {
+            let mut soft_refs = self.soft_refs.lock().unwrap();
+            soft_refs.retain(|(target, alive)| target.is_valid() && alive.strong_count() > 0);
+            if !under_pressure {
+                soft_roots.extend(
+                    soft_refs
+                        .iter()
+                        .map(|(target, _)| target.as_wrapper_ptr() as usize),
+                );
+            }
+        }
+
+        let young = self.young.lock().unwrap();
+        let large = self.large.lock().unwrap();
+        let liveness = self.liveness.lock().unwrap();
+        for (index, r) in refs.iter().enumerate() {
+            let internal = internal_refs
+                .get(&(r.as_ref() as *const T as usize))
+                .copied()
+                .unwrap_or(0);
+            if r.is_pinned()
+                || young.contains_key(&r.id())
+                || large.contains(&(r.as_wrapper_ptr() as usize))
+                || soft_roots.contains(&(r.as_wrapper_ptr() as usize))
+                || r.strong_ref()
+                    > r.inner()
+                        .attached_gc_count
+                        .load(std::sync::atomic::Ordering::Relaxed)
+                        + internal
+                // 存活条件由用户提供，开销未知，放在最后只对其他条件都不成立的对象调用
+                || liveness.get(&r.id()).is_some_and(|alive| alive())
             {
                 // 当强引用计数大于 `attached_gc_count` 时，说明 GC 堆外存在对象（比如VM栈或其他 GCArc 的引用）则认为其为根对象
-                queue.push_back(r.as_weak());
+                roots.push(index);
             }
         }
 
+        (marked, roots)
+    }
+
+    /// 标记阶段的第二步：从根对象开始遍历对象图，将所有可达的对象标记为存活
+    /// 调用者必须在整个遍历期间持有 `gc_refs` 的锁：队列中被追踪的对象只以地址表示，由追踪列表中的强引用保证存活
+    fn trace(
+        &self,
+        marked: &mut FxHashMap<usize, bool>,
+        mut queue: VecDeque<Pending<T>>,
+        stats: &mut CollectionStats,
+    ) {
+        // BFS 的层次信息：`level` 为当前处理的层数（根对象为第1层），
+        // `level_remaining` 为当前层中尚未处理的队列元素数。
+        let mut level = 1;
+        let mut level_remaining = queue.len();
+
+        // 入队前去重：已标记的对象和已在队列中的对象不再入队，避免被多个对象引用的子对象在队列中重复出现，
+        // 从而限制队列长度并减少重复的升级。键为 `GCWrapper` 地址，与 `marked` 的键（值的地址）相同。
+        // 对象离开队列时移除其键，因此集合中的地址总有追踪列表中的强引用或队列中的弱引用保证不被复用。
+        let mut queued: rustc_hash::FxHashSet<usize> = queue.iter().map(Pending::key).collect();
+        let mut children = VecDeque::new();
+
         // 开始标记阶段的遍历。
         // 当队列不为空时，持续处理队列中的对象。
         while !queue.is_empty() {
-            // 从队列前端取出一个弱引用。
+            // 当前层处理完毕时，队列中剩余的元素全部属于下一层。
+            if level_remaining == 0 {
+                level += 1;
+                level_remaining = queue.len();
+            }
+            stats.max_queue_len = stats.max_queue_len.max(queue.len());
+
+            // 从队列前端取出一个对象。
             // `unwrap()` 在这里是安全的，因为我们刚检查了 `!queue.is_empty()`。
-            let current_weak = queue.pop_front().unwrap();
+            let current = queue.pop_front().unwrap();
+            level_remaining -= 1;
+            let current_ptr = current.key();
+            queued.remove(&current_ptr);
 
-            // 尝试将弱引用升级为强引用。
-            // 如果升级失败（返回 `None`），意味着该对象已经被释放，
-            // 或者在加入队列后、处理前其强引用计数变为0，所以跳过它。
-            let Some(current_strong) = current_weak.upgrade() else {
-                continue; // 对象已被释放或不再可达
+            // 被追踪的对象直接访问；未被追踪的对象需要将弱引用升级为强引用，
+            // 升级失败意味着该对象在加入队列后、处理前已经被释放，跳过它。
+            let untracked_strong;
+            let current_value: &T = match current {
+                // SAFETY: 地址来自追踪列表中的对象，调用者在遍历期间持有 `gc_refs` 的锁，其中的强引用保证对象存活
+                Pending::Tracked(key) => unsafe { &*(key as *const T) },
+                Pending::Untracked(weak) => {
+                    let Some(strong) = weak.upgrade_untracked() else {
+                        continue; // 对象已被释放
+                    };
+                    untracked_strong = strong;
+                    untracked_strong.as_ref()
+                }
             };
 
-            // 获取当前强引用指向对象的内存地址。
-            let current_ptr = current_strong.as_ref() as *const T as usize;
-
             // 检查该对象是否已经被标记过。
             // `unwrap_or(&false)` 处理了理论上不应发生的情况（对象不在 `marked` 中），
             // 或者对象已在 `marked` 中且值为`true`。
@@ -179,242 +742,4249 @@ where
 
             // 将当前对象标记为“存活”（设置为 `true`）。
             marked.insert(current_ptr, true);
+            self.progress
+                .marked_so_far
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            stats.max_depth = stats.max_depth.max(level);
+
+            // 如果当前对象是冻结子图的根，则直接将整个子图标记为存活，无需重新遍历。
+            // 冻结子图是根对象在冻结时的完整可达集合，其中对象的引用不会指向集合之外。
+            if let Some(frozen) = self.frozen.get(&current_ptr) {
+                for key in frozen.member_keys.iter() {
+                    if let Some(member) = marked.get_mut(key) {
+                        if !*member {
+                            self.progress
+                                .marked_so_far
+                                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                        }
+                        *member = true;
+                    }
+                }
+                continue;
+            }
 
             // 访问当前对象，并收集它引用的其他GC管理的对象。
-            // `GCTraceable::collect` 方法负责将当前对象内部引用的其他
-            // `GCArcWeak<T>` 添加到 `queue` 中，以便后续处理。
-            current_strong.as_ref().collect(&mut queue);
-        }        // 清除阶段（Sweep Phase）。
-        // 根据 `marked` 表中的标记状态，筛选出所有存活的对象。
-        // `retained` 向量将只包含那些在标记阶段被标记为 `true` 的对象。
-        let retained: Vec<GCArc<T>> = refs
-            .iter()
-            .filter(|r| {
-                let ptr = r.as_ref() as *const T as usize;
-                // 如果对象在 `marked` 表中为 `true`，则保留它。
-                // `unwrap_or(&false)` 确保如果对象由于某种原因不在 `marked` 中（不应发生），
-                // 它将被视为未标记，从而被回收。
-                let retain = *marked.get(&ptr).unwrap_or(&false);
-                if !retain {
-                    // 如果对象未被标记为存活，则减少持有的 GC 实例数，因为其将被立即移出堆
-                    r.inner()
-                        .attached_gc_count
-                        .fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
-                    
-                    // 从内存计数中减去被回收对象的大小
-                    let obj_size = std::mem::size_of::<T>() + std::mem::size_of::<GCArc<T>>();
-                    self.allocated_memory
-                        .fetch_sub(obj_size, std::sync::atomic::Ordering::Relaxed);
-                }
-                retain
-            })
-            .cloned() // 克隆 GCArc<T> 以便在新向量中拥有它们的所有权。
-            .collect();
+            // `GCTraceable::collect`（或 `GCTraceable::edges`）负责报告当前对象内部引用的其他对象，
+            // 被追踪的子对象以地址入队，报告的弱引用在此立即丢弃；只有未被追踪的子对象保留弱引用。
+            trace_children(current_value, &mut children);
+            for child in children.drain(..) {
+                let key = child.as_wrapper_ptr() as usize;
+                if *marked.get(&key).unwrap_or(&false) || !queued.insert(key) {
+                    continue;
+                }
+                queue.push_back(Pending::new(child, marked));
+            }
+        }
+    }
 
-        // 清空旧的 `refs` 列表。
-        refs.clear();
-        // 将所有存活的对象添加回 `refs` 列表。
-        // 此时，`refs` 只包含标记阶段确认存活的对象。
-        // 那些未被标记的对象（即 `retained` 中没有的对象）的 `GCArc` 将会在这里被丢弃。
-        // 如果这些是最后的强引用，对象本身将被 `Drop`。
-        refs.extend(retained);
+    pub fn collect(&mut self) {
+        if self.weak_tracking {
+            self.collect_weak_tracked();
+            return;
+        }
 
-        // 重置 `attach_count` 计数器。
-        // `attach_count` 用于启发式地决定何时运行垃圾回收。
-        // 在一次完整的回收之后，这个计数器被重置为0。
-        self.attach_count
-            .store(0, std::sync::atomic::Ordering::Relaxed);
-    }
-    pub fn object_count(&self) -> usize {
-        return self.gc_refs.lock().unwrap().len();
-    }
+        // 执行垃圾回收过程。
+        // 该过程分为两个主要阶段：标记（Mark）和清除（Sweep）。
+        // 1. 标记阶段：从根对象开始，遍历所有可达的对象，并将其标记为“存活”。
+        // 2. 清除阶段：遍历所有GC管理的对象，回收所有未被标记为“存活”的对象。
 
-    pub fn get_all(&self) -> Vec<GCArc<T>> {
-        self.gc_refs.lock().unwrap().clone()
+        if self.tracked_is_empty() {
+            self.collect_empty();
+            return;
+        }
+
+        // 根对象按引用计数等规则找出（与 `RefcountRootScanner` 相同，但直接以下标表示而不创建弱引用），
+        // 之后的标记和清除与 `collect_with_scanner` 共用同一路径。
+        // 未被标记的对象的 `GCArc` 将会在清除后被丢弃，如果这些是最后的强引用，对象本身将被 `Drop`。
+        self.collect_from(None);
     }
 
-    pub fn create(&mut self, obj: T) -> GCArc<T> {
-        let gc_arc = GCArc::new(obj);
-        self.attach(&gc_arc);
-        gc_arc
+    /// 为对象注册存活条件：每次回收的标记阶段调用 `alive`，返回 `true` 时对象被视为根对象，
+    /// 从而把对象的存活与GC之外的某个对象的生命周期绑定（例如 `alive` 检查一个 `std::sync::Weak` 是否仍然有效）。
+    /// 同一对象再次注册会替换之前的条件；对象被回收、`detach` 或 `drain` 后条件随之移除。
+    /// `alive` 在持有追踪列表锁的情况下被调用，不能调用同一个GC的方法。弱引用追踪模式下GC不持有对象，条件不起作用
+    pub fn keep_while<F>(&mut self, gc_arc: &GCArc<T>, alive: F)
+    where
+        F: Fn() -> bool + Send + 'static,
+    {
+        self.liveness
+            .lock()
+            .unwrap()
+            .insert(gc_arc.id(), Box::new(alive));
     }
 
-    /// 获取当前分配的内存估算值（字节）
-    pub fn allocated_memory(&self) -> usize {
-        self.allocated_memory.load(std::sync::atomic::Ordering::Relaxed)
+    /// 执行一次回收，根对象完全由 `scanner` 枚举，而不使用基于引用计数的判断
+    /// 适用于能够准确枚举根（VM栈、寄存器、全局变量等）的嵌入者：未被报告、也不能从报告的根到达的对象都会被回收，
+    /// 即使它们仍被外部持有（此时对象只是被移出GC追踪，不会被释放）。
+    /// 固定、宽限期、大对象空间、软引用和 `keep_while` 只在扫描器自己考虑它们时起作用（`RefcountRootScanner` 会考虑）。
+    /// 弱引用追踪模式下等同于 `collect()`
+    pub fn collect_with_scanner(&mut self, scanner: &dyn RootScanner<T>) {
+        if self.weak_tracking {
+            self.collect_weak_tracked();
+            return;
+        }
+        if self.tracked_is_empty() {
+            self.collect_empty();
+            return;
+        }
+        self.collect_from(Some(scanner));
     }
 
-    /// 设置内存阈值，None表示禁用内存阈值触发
-    pub fn set_memory_threshold(&mut self, threshold: Option<usize>) {
-        self.memory_threshold = threshold;
+    /// `collect` 和 `collect_with_scanner` 共用的标记、清除和释放，`scanner` 的含义见 `mark_and_sweep_batched`
+    fn collect_from(&mut self, scanner: Option<&dyn RootScanner<T>>) {
+        let mut garbage = Vec::new();
+        let mut swept_ids =
+            self.mark_and_sweep_batched(usize::MAX, scanner, |batch| garbage = batch);
+        let resurrected = self.release_garbage(garbage);
+        swept_ids.retain(|id| !resurrected.contains(id));
+        self.finish_collection(&swept_ids);
     }
 
-    /// 获取当前内存阈值
-    pub fn memory_threshold(&self) -> Option<usize> {
-        self.memory_threshold
-    }    fn should_collect(&self) -> bool {
-        let current_count = self.gc_refs.lock().unwrap().len();
-        let attach_count = self.attach_count.load(std::sync::atomic::Ordering::Relaxed);
-        let current_memory = self.allocated_memory.load(std::sync::atomic::Ordering::Relaxed);
+    /// 执行一次回收，本次回收中额外将 `keep` 中的对象视为根对象，它们及其可达的对象都会存活
+    /// 适用于只需在本次回收中保证存活的对象（例如仍在进行中的异步任务引用的对象），比永久固定更灵活。
+    /// `keep` 中已失效的弱引用会被忽略
+    pub fn collect_keeping(&mut self, keep: &[GCArcWeak<T>]) {
+        // 回收期间持有的强引用使这些对象被识别为存在外部引用的根对象
+        let kept: Vec<GCArc<T>> = keep.iter().filter_map(|r| r.upgrade_untracked()).collect();
+        self.collect();
+        drop(kept);
+    }
 
-        if current_count == 0 {
-            return false;
+    /// 只回收以 `root` 为根的子图：释放从 `root` 可达、但从其他任何根对象都不可达的对象，返回被回收的对象数
+    /// 适用于卸载场景或模块等相互独立的大子图，无需对整个堆进行回收。
+    /// `root` 本身也会被移出追踪（调用者持有的引用不使其成为根对象），被其他根对象共享的对象保持不变。
+    /// 弱引用追踪模式下GC不持有对象，此方法不做任何事并返回0
+    pub fn collect_subtree(&mut self, root: &GCArc<T>) -> usize {
+        if self.weak_tracking {
+            return 0;
         }
 
-        // 检查内存阈值
-        if let Some(memory_threshold) = self.memory_threshold {
-            if current_memory >= memory_threshold {
-                return true;
-            }
+        self.emit(CollectionEvent::CollectionStarted);
+        self.progress.begin();
+        let obj_size = std::mem::size_of::<T>() + std::mem::size_of::<GCArc<T>>();
+        let mut garbage = Vec::new();
+        let mut swept_ids = Vec::new();
+        {
+            // 与 `collect` 相同，标记和清除期间一直持有锁
+            let mut refs = self.gc_refs.lock().unwrap();
+            let (mut reachable_elsewhere, mut roots) = self.mark_roots(&refs);
+            let mut in_subtree = reachable_elsewhere.clone();
+            roots.retain(|&index| !GCArc::ptr_eq(&refs[index], root));
+
+            // 分别计算从其他根对象可达的对象和从 `root` 可达的对象
+            let mut stats = CollectionStats::default();
+            let queue = Pending::from_roots(&refs, &roots);
+            self.trace(&mut reachable_elsewhere, queue, &mut stats);
+            let queue = VecDeque::from([Pending::new(root.as_weak(), &in_subtree)]);
+            self.trace(&mut in_subtree, queue, &mut stats);
+
+            let mut young = self.young.lock().unwrap();
+            let mut large = self.large.lock().unwrap();
+            refs.retain(|r| {
+                let ptr = r.as_ref() as *const T as usize;
+                if !in_subtree.get(&ptr).copied().unwrap_or(false)
+                    || reachable_elsewhere.get(&ptr).copied().unwrap_or(true)
+                {
+                    return true;
+                }
+                r.inner().release_attachment();
+                self.allocated_memory
+                    .fetch_sub(obj_size, std::sync::atomic::Ordering::Relaxed);
+                young.remove(&r.id());
+                large.remove(&(r.as_wrapper_ptr() as usize));
+                swept_ids.push(r.id());
+                self.progress
+                    .swept_so_far
+                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                garbage.push(r.clone());
+                false
+            });
         }
+        self.progress.end();
 
-        // 检查百分比阈值：当attach次数超过当前对象数的指定百分比时触发回收
-        let threshold = (current_count * self.collection_percentage) / 100;
-        attach_count >= threshold.max(1) // 至少1次attach才触发
+        let resurrected = self.release_garbage(garbage);
+        swept_ids.retain(|id| !resurrected.contains(id));
+        self.finish_collection(&swept_ids);
+        swept_ids.len()
     }
-}
 
-impl<T> Drop for GC<T>
-where
-    T: GCTraceable<T> + 'static,
-{    fn drop(&mut self) {
-        // 在垃圾回收器被销毁时，清理所有跟踪的对象。
-        // 这将触发所有对象的 `Drop` 实现。
+    /// 执行一次包括大对象空间在内的完整回收
+    /// `collect()` 和自动回收把大对象视为根对象：大对象本身不会被回收，但它们引用的对象仍会被正常标记
+    pub fn collect_major(&mut self) {
+        let large = std::mem::take(&mut *self.large.lock().unwrap());
+        self.collect();
+        if large.is_empty() {
+            return;
+        }
+
+        // 仍被追踪的大对象放回大对象空间；在任何新对象attach之前完成，被释放对象的地址不会被误认
+        let refs = self.gc_refs.lock().unwrap();
+        self.large.lock().unwrap().extend(
+            refs.iter()
+                .map(|r| r.as_wrapper_ptr() as usize)
+                .filter(|key| large.contains(key)),
+        );
+    }
+
+    /// 执行一次回收后，若内存估算仍超过内存阈值，则淘汰最久未被访问的存活对象，
+    /// 直到被追踪的对象数降至回收后对象数的 `keep_fraction`，返回被淘汰的对象数。
+    /// 注意：被淘汰的对象即使仍然可达也会被移出GC，仅通过其他对象的弱引用可达的对象会因此被释放；
+    /// 根对象（存在外部强引用、被固定或处于宽限期）不会被淘汰。
+    /// 访问先后依据 `GCArc::last_access`，即对象创建或最近一次通过 `GCArcWeak::upgrade` 升级的时刻。
+    /// 未设置内存阈值或处于弱引用追踪模式时等同于 `collect()`。需要启用 `access-tracking` feature
+    #[cfg(feature = "access-tracking")]
+    pub fn collect_lru(&mut self, keep_fraction: f64) -> usize {
+        self.collect();
+        let Some(threshold) = self.memory_threshold else {
+            return 0;
+        };
+        if self.weak_tracking || self.allocated_memory() <= threshold {
+            return 0;
+        }
+
+        self.emit(CollectionEvent::CollectionStarted);
         let mut refs = self.gc_refs.lock().unwrap();
-        for gc_arc in refs.drain(..) {
-            // 减少 `attached_gc_count`，表示该对象不再被垃圾回收器跟踪。
-            gc_arc
-                .inner()
+        let keep = (refs.len() as f64 * keep_fraction.clamp(0.0, 1.0)) as usize;
+        let excess = refs.len().saturating_sub(keep);
+
+        // 按最近访问时间从早到晚排列非根对象，淘汰其中最早的 `excess` 个
+        let (_, roots) = self.mark_roots(&refs);
+        let root_keys: rustc_hash::FxHashSet<usize> = roots
+            .iter()
+            .map(|&index| refs[index].as_wrapper_ptr() as usize)
+            .collect();
+        let mut candidates: Vec<(u64, usize)> = refs
+            .iter()
+            .filter(|r| !root_keys.contains(&(r.as_wrapper_ptr() as usize)))
+            .map(|r| (r.last_access(), r.as_wrapper_ptr() as usize))
+            .collect();
+        candidates.sort_unstable();
+        let evicted: rustc_hash::FxHashSet<usize> = candidates
+            .into_iter()
+            .take(excess)
+            .map(|(_, key)| key)
+            .collect();
+
+        let obj_size = std::mem::size_of::<T>() + std::mem::size_of::<GCArc<T>>();
+        let mut garbage = Vec::with_capacity(evicted.len());
+        let mut evicted_ids = Vec::with_capacity(evicted.len());
+        refs.retain(|r| {
+            if !evicted.contains(&(r.as_wrapper_ptr() as usize)) {
+                return true;
+            }
+            r.inner().release_attachment();
+            self.allocated_memory
+                .fetch_sub(obj_size, std::sync::atomic::Ordering::Relaxed);
+            evicted_ids.push(r.id());
+            garbage.push(r.clone());
+            false
+        });
+        drop(refs);
+
+        let resurrected = self.release_garbage(garbage);
+        evicted_ids.retain(|id| !resurrected.contains(id));
+        self.finish_collection(&evicted_ids);
+        evicted_ids.len()
+    }
+
+    /// 执行垃圾回收，并报告每个被追踪对象（以id标识）的结局以及被保留的原因，用于理解回收器的行为和排查意外的存活
+    /// 弱引用追踪模式下仍然存活的对象只可能因外部引用存活，均报告为 `ObjectFate::KeptRoot`
+    pub fn collect_explained(&mut self) -> Vec<(u64, ObjectFate)> {
+        if self.weak_tracking {
+            let fates = self
+                .weak_refs
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(r, id)| {
+                    let fate = if r.is_valid() {
+                        ObjectFate::KeptRoot
+                    } else {
+                        ObjectFate::Freed
+                    };
+                    (*id, fate)
+                })
+                .collect();
+            self.collect_weak_tracked();
+            return fates;
+        }
+
+        // 回收前先按根对象的判断规则记录每个对象被保留的原因，
+        // 之后的标记阶段在同样的状态下进行，因此根对象的集合与这里一致。
+        let mut fates: Vec<(u64, ObjectFate)> = {
+            let refs = self.gc_refs.lock().unwrap();
+            let (_, roots) = self.mark_roots(&refs);
+            let root_keys: rustc_hash::FxHashSet<usize> = roots
+                .iter()
+                .map(|&index| refs[index].as_wrapper_ptr() as usize)
+                .collect();
+            refs.iter()
+                .map(|r| {
+                    let fate = if r.is_pinned() {
+                        ObjectFate::KeptPinned
+                    } else if root_keys.contains(&(r.as_wrapper_ptr() as usize)) {
+                        ObjectFate::KeptRoot
+                    } else {
+                        ObjectFate::KeptReachable
+                    };
+                    (r.id(), fate)
+                })
+                .collect()
+        };
+
+        let (garbage, mut swept_ids) = self.mark_and_sweep();
+        let resurrected = self.release_garbage(garbage);
+        swept_ids.retain(|id| !resurrected.contains(id));
+        self.finish_collection(&swept_ids);
+
+        // 被复活的对象重新成为根对象，仍报告为 `ObjectFate::KeptRoot`
+        let swept: rustc_hash::FxHashSet<u64> = swept_ids.into_iter().collect();
+        for (id, fate) in fates.iter_mut() {
+            if swept.contains(id) {
+                *fate = ObjectFate::Freed;
+            } else if resurrected.contains(id) {
+                *fate = ObjectFate::KeptRoot;
+            }
+        }
+        fates
+    }
+
+    /// 释放被回收的对象，并处理析构过程中的对象复活，返回被复活对象的id
+    ///
+    /// 两阶段协议：
+    /// 1. 释放（`release_batch`）：先为每个待释放对象记录其外部持有者数，即扣除GC持有的引用和其他待释放对象的强引用边之后
+    ///    剩余的强引用数，再逐个释放。GC持有的是最后一个强引用时，由 `try_unwrap` 原子地取出值交给 `release`，
+    ///    此后对象不可能再被复活；否则只丢弃GC持有的引用，保留一个弱引用和记录下的外部持有者数。
+    /// 2. 复查（`resurrected`）：所有对象都已释放（所有 `Drop` 都已运行）之后，第一阶段保留的对象中仍然存活、
+    ///    且持有者多于追踪它的GC与记录下的外部持有者之和的对象已被复活——例如先被释放的对象在 `Drop` 中升级弱引用
+    ///    并把它保存了起来：将其重新加入GC追踪，避免对象继续存活却脱离GC管理。
+    ///
+    /// 复查比较的是全部析构完成之后的引用计数，此时其他被回收对象的强引用边都已释放，
+    /// 因此复活与释放强引用边同时发生时不会相互抵消而被漏掉。
+    /// 所有清除路径都经由此方法（或 `release_batch`、`resurrected` 加 `readmit`）释放对象，因此复活的处理与回收方式无关
+    fn release_garbage(&mut self, garbage: Vec<GCArc<T>>) -> Vec<u64> {
+        self.release_garbage_with(garbage, drop)
+    }
+
+    /// 与 `release_garbage` 相同，但由 `release` 处置每个已被GC独占的对象的值（例如放入对象池）
+    fn release_garbage_with<F>(&mut self, garbage: Vec<GCArc<T>>, release: F) -> Vec<u64>
+    where
+        F: FnMut(T),
+    {
+        let (panics, pending) = Self::release_batch(garbage, release);
+        self.drop_panics.lock().unwrap().extend(panics);
+        self.readmit(Self::resurrected(pending))
+    }
+
+    /// 两阶段协议的释放阶段：不访问GC，可以在其他线程中执行
+    /// 返回捕获的panic，以及释放时仍被其他地方引用的对象，需在所有批次释放完毕后交给 `resurrected` 复查
+    fn release_batch<F>(
+        garbage: Vec<GCArc<T>>,
+        mut release: F,
+    ) -> (Vec<Box<dyn Any + Send>>, Vec<Survivor<T>>)
+    where
+        F: FnMut(T),
+    {
+        // 在释放任何对象之前记录每个对象的外部持有者数：强引用计数减去本GC持有的引用、其他GC持有的引用
+        // 以及同一批中其他待释放对象的强引用边。后者会随这些对象的释放而消失，不属于复查时应当保留的持有者
+        let mut internal_refs: FxHashMap<usize, usize> = FxHashMap::default();
+        if T::TRACES_STRONG_EDGES {
+            let mut edges = Vec::new();
+            for gc_arc in garbage.iter() {
+                gc_arc.as_ref().edges(&mut edges);
+                for edge in edges.drain(..) {
+                    if let Edge::Strong(child) = edge {
+                        *internal_refs
+                            .entry(child.as_wrapper_ptr() as usize)
+                            .or_default() += 1;
+                    }
+                }
+            }
+        }
+        let outside: Vec<usize> = garbage
+            .iter()
+            .map(|r| {
+                let internal = internal_refs
+                    .get(&(r.as_wrapper_ptr() as usize))
+                    .copied()
+                    .unwrap_or(0);
+                r.strong_ref()
+                    .saturating_sub(1 + r.tracked_by_count() + internal)
+            })
+            .collect();
+
+        Self::release_strong_edges(&garbage);
+        let mut panics = Vec::new();
+        let mut pending = Vec::new();
+        for (gc_arc, outside) in garbage.into_iter().zip(outside) {
+            let result = match gc_arc.try_unwrap() {
+                Ok(value) => catch_unwind(AssertUnwindSafe(|| release(value))),
+                Err(shared) => {
+                    pending.push(Survivor {
+                        weak: shared.as_weak(),
+                        outside,
+                    });
+                    // 其他持有者可能恰好在此时释放了引用，对象仍可能在这里被析构
+                    catch_unwind(AssertUnwindSafe(|| drop(shared)))
+                }
+            };
+            if let Err(panic) = result {
+                panics.push(panic);
+            }
+        }
+        (panics, pending)
+    }
+
+    /// 两阶段协议的复查阶段：返回 `pending` 中在所有对象释放完毕后被复活的对象，
+    /// 即除追踪它的GC和释放前记录的外部持有者之外仍有其他持有者的对象
+    fn resurrected(pending: Vec<Survivor<T>>) -> Vec<GCArc<T>> {
+        pending
+            .into_iter()
+            .filter_map(|survivor| {
+                let gc_arc = survivor.weak.upgrade_untracked()?;
+                // 减去复查时升级得到的引用
+                let holders = gc_arc.strong_ref() - 1;
+                (holders > gc_arc.tracked_by_count() + survivor.outside).then_some(gc_arc)
+            })
+            .collect()
+    }
+
+    /// 将被复活的对象重新加入GC追踪，返回它们的id
+    fn readmit(&mut self, resurrected: Vec<GCArc<T>>) -> Vec<u64> {
+        let obj_size = std::mem::size_of::<T>() + std::mem::size_of::<GCArc<T>>();
+        let mut refs = self.gc_refs.lock().unwrap();
+        resurrected
+            .into_iter()
+            .map(|gc_arc| {
+                gc_arc
+                    .inner()
+                    .attached_gc_count
+                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                self.allocated_memory
+                    .fetch_add(obj_size, std::sync::atomic::Ordering::Relaxed);
+                let id = gc_arc.id();
+                refs.push(gc_arc);
+                self.dirty.store(true, std::sync::atomic::Ordering::Relaxed);
+                id
+            })
+            .collect()
+    }
+
+    /// 在释放被清除的对象之前丢弃它们之间的强引用边，否则被回收的强引用环会互相保持存活而泄漏
+    fn release_strong_edges(garbage: &[GCArc<T>]) {
+        if !T::TRACES_STRONG_EDGES {
+            return;
+        }
+        for gc_arc in garbage {
+            gc_arc.as_ref().release_strong_edges();
+        }
+    }
+
+    /// 取出并清空回收过程中被捕获的对象 `Drop` panic，调用者可以记录它们或使用
+    /// `std::panic::resume_unwind` 重新抛出
+    pub fn take_drop_panics(&mut self) -> Vec<Box<dyn Any + Send>> {
+        std::mem::take(&mut *self.drop_panics.lock().unwrap())
+    }
+
+    /// 执行垃圾回收但不丢弃被回收的对象，而是将其内部的 `T` 取出放入 `pool` 中以便复用，
+    /// 从而减少分配器的开销。
+    /// 放入 `pool` 的值可能处于任意状态（例如仍然持有指向其他已回收对象的失效弱引用），
+    /// 调用者在复用前需要自行重置它们。
+    /// 值在被取出时不会被析构，因此不会发生 `release_garbage` 所处理的复活。
+    /// 无法取出的对象（对真正的垃圾而言不应发生）会被正常丢弃。
+    /// 弱引用追踪模式下GC不持有对象，此方法等同于 `collect()`。
+    pub fn collect_into_pool(&mut self, pool: &mut Vec<T>) {
+        if self.weak_tracking {
+            self.collect_weak_tracked();
+            return;
+        }
+
+        let (garbage, mut swept_ids) = self.mark_and_sweep();
+        let resurrected = self.release_garbage_with(garbage, |value| pool.push(value));
+        swept_ids.retain(|id| !resurrected.contains(id));
+        self.finish_collection(&swept_ids);
+    }
+
+    /// 执行垃圾回收，并在释放被回收的对象之前先调用它们的 `GCTraceable::clear_edges`
+    /// 清空所有对象的引用后再逐个释放，从而打断对象之间的引用链，避免长链导致的深度递归析构。
+    /// `clear_edges` 只会在即将被释放的对象上调用。
+    /// 所有值都先被取出再析构，析构时其他被回收的对象已经不存在，无法再通过弱引用复活它们。
+    /// 弱引用追踪模式下GC不持有对象，此方法等同于 `collect()`。
+    pub fn break_cycles(&mut self) {
+        if self.weak_tracking {
+            self.collect_weak_tracked();
+            return;
+        }
+
+        let mut values = Vec::new();
+        let (garbage, mut swept_ids) = self.mark_and_sweep();
+        let resurrected = self.release_garbage_with(garbage, |mut value| {
+            value.clear_edges();
+            values.push(value);
+        });
+        swept_ids.retain(|id| !resurrected.contains(id));
+
+        let mut panics = Vec::new();
+        for value in values {
+            if let Err(panic) = catch_unwind(AssertUnwindSafe(|| drop(value))) {
+                panics.push(panic);
+            }
+        }
+        self.drop_panics.lock().unwrap().extend(panics);
+        self.finish_collection(&swept_ids);
+    }
+
+    /// 流水线式的垃圾回收：清除阶段每分出一批待回收对象，就立即交给后台线程释放，
+    /// 使对象的 `Drop` 和内存释放与剩余的清除工作重叠进行。
+    /// 对象只有在标记阶段完全结束后才会被清除，因此不会回收任何仍可达的对象。
+    /// 要求 `T: Send + Sync`，因为对象会在另一个线程中被释放。
+    pub fn collect_pipelined(&mut self)
+    where
+        T: Send + Sync,
+    {
+        if self.weak_tracking {
+            self.collect_weak_tracked();
+            return;
+        }
+
+        let (panics, resurrected, mut swept_ids) = std::thread::scope(|scope| {
+            let (sender, receiver) = std::sync::mpsc::channel::<Vec<GCArc<T>>>();
+            let dropper = scope.spawn(move || {
+                let mut panics = Vec::new();
+                let mut pending = Vec::new();
+                for batch in receiver {
+                    let (batch_panics, batch_pending) = Self::release_batch(batch, drop);
+                    panics.extend(batch_panics);
+                    pending.extend(batch_pending);
+                }
+                // 之后的批次可能持有指向之前批次中对象的强引用边，复查必须等所有批次都释放完毕
+                (panics, Self::resurrected(pending))
+            });
+            // `sender` 随闭包一起在清除结束后被丢弃，后台线程随之退出
+            let swept_ids = self.mark_and_sweep_batched(PIPELINED_SWEEP_BATCH, None, move |batch| {
+                let _ = sender.send(batch);
+            });
+            let (panics, resurrected) = dropper
+                .join()
+                .unwrap_or_else(|panic| (vec![panic], Vec::new()));
+            (panics, resurrected, swept_ids)
+        });
+        self.drop_panics.lock().unwrap().extend(panics);
+        let resurrected = self.readmit(resurrected);
+        swept_ids.retain(|id| !resurrected.contains(id));
+        self.finish_collection(&swept_ids);
+    }
+
+    /// 执行标记和清除阶段，将未被标记的对象移出追踪列表，返回这些对象及其id
+    fn mark_and_sweep(&mut self) -> (Vec<GCArc<T>>, Vec<u64>) {
+        let mut garbage = Vec::new();
+        let swept_ids = self.mark_and_sweep_batched(usize::MAX, None, |batch| garbage = batch);
+        (garbage, swept_ids)
+    }
+
+    /// 执行标记和清除阶段，每当积累 `batch_size` 个待回收对象时就将其交给 `on_garbage` 处理，
+    /// 返回所有被回收对象的id。`scanner` 不为 `None` 时由它在持有锁期间枚举根对象，代替基于引用计数的根对象判断
+    fn mark_and_sweep_batched<F>(
+        &mut self,
+        batch_size: usize,
+        scanner: Option<&dyn RootScanner<T>>,
+        mut on_garbage: F,
+    ) -> Vec<u64>
+    where
+        F: FnMut(Vec<GCArc<T>>),
+    {
+        self.emit(CollectionEvent::CollectionStarted);
+        // 没有被追踪的对象时不可能产生垃圾，直接跳过标记表和队列的分配。
+        if self.tracked_is_empty() {
+            self.last_collection_stats = CollectionStats::default();
+            self.attach_count
+                .store(0, std::sync::atomic::Ordering::Relaxed);
+            return Vec::new();
+        }
+
+        // 整个标记和清除阶段都持有 `gc_refs` 的锁。回收需要 `&mut self`，其他线程本就无法在回收期间
+        // attach或detach，因此在标记期间释放锁并不能让其他线程更早取得进展。
+        // `refs` 存储了所有由GC跟踪的 GCArc<T> 对象，它们持有的强引用保证标记期间对象不会被释放。
+        self.progress.begin();
+        let mut refs = self.gc_refs.lock().unwrap();
+        #[cfg(feature = "tracing")]
+        let mark_span = tracing::debug_span!(
+            "mark",
+            tracked = refs.len(),
+            scanned = tracing::field::Empty
+        )
+        .entered();
+        let (mut marked, queue) = match scanner {
+            Some(scanner) => {
+                let mut roots = VecDeque::new();
+                scanner.scan_roots(self, &refs, &mut roots);
+                let marked = refs
+                    .iter()
+                    .map(|r| (r.as_ref() as *const T as usize, false))
+                    .collect();
+                let queue = roots
+                    .into_iter()
+                    .map(|root| Pending::new(root, &marked))
+                    .collect();
+                (marked, queue)
+            }
+            None => {
+                let (marked, roots) = self.mark_roots(&refs);
+                (marked, Pending::from_roots(&refs, &roots))
+            }
+        };
+
+        // 标记阶段
+        let mut stats = CollectionStats {
+            outstanding_get_all: self
+                .outstanding_get_all
+                .load(std::sync::atomic::Ordering::Relaxed),
+            ..CollectionStats::default()
+        };
+        self.trace(&mut marked, queue, &mut stats);
+        self.last_collection_stats = stats;
+        #[cfg(feature = "tracing")]
+        {
+            let scanned = self
+                .progress
+                .marked_so_far
+                .load(std::sync::atomic::Ordering::Relaxed);
+            mark_span.record("scanned", scanned);
+            mark_span.exit();
+        }
+
+        // 清除阶段（Sweep Phase）。
+        #[cfg(feature = "tracing")]
+        let sweep_span = tracing::debug_span!("sweep", freed = tracing::field::Empty).entered();
+        // 根据 `marked` 表中的标记状态，将对象划分为存活的 `retained` 和待回收的 `garbage`。
+        // `retained` 向量将包含那些在标记阶段被标记为 `true` 的对象。
+        let mut retained = Vec::with_capacity(refs.len());
+        let mut garbage = Vec::new();
+        let mut swept_ids = Vec::new();
+        for r in refs.drain(..) {
+            let ptr = r.as_ref() as *const T as usize;
+            // 如果对象在 `marked` 表中为 `true`，则保留它。
+            if *marked.get(&ptr).unwrap_or(&true) {
+                retained.push(r);
+                continue;
+            }
+
+            // 如果对象未被标记为存活，则减少持有的 GC 实例数，因为其将被立即移出堆。
+            // 对象来自本GC的 `refs`，因此本GC一定追踪过它，计数至少为1。
+            r.inner().release_attachment();
+
+            // 从内存计数中减去被回收对象的大小
+            let obj_size = std::mem::size_of::<T>() + std::mem::size_of::<GCArc<T>>();
+            self.allocated_memory
+                .fetch_sub(obj_size, std::sync::atomic::Ordering::Relaxed);
+
+            swept_ids.push(r.id());
+            self.progress
+                .swept_so_far
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            garbage.push(r);
+            if garbage.len() >= batch_size {
+                on_garbage(std::mem::take(&mut garbage));
+            }
+        }
+        if !garbage.is_empty() {
+            on_garbage(garbage);
+        }
+        // 被清除的对象随后可能因复活而被重新追踪，此处记录的是清除出追踪列表的对象数
+        #[cfg(feature = "tracing")]
+        {
+            sweep_span.record("freed", swept_ids.len());
+            sweep_span.exit();
+        }
+
+        // 将所有存活的对象放回 `refs` 列表。
+        // 此时，`refs` 只包含标记阶段确认存活的对象。
+        refs.extend(retained);
+
+        // 宽限期中的对象又经历了一次回收。
+        // 以id为键，已不再被追踪的对象留下的条目不会与复用其地址的新对象混淆，并会在宽限期结束时自然移除。
+        self.young.lock().unwrap().retain(|_, remaining| {
+            *remaining -= 1;
+            *remaining > 0
+        });
+
+        // 重置 `attach_count` 计数器。
+        // `attach_count` 用于启发式地决定何时运行垃圾回收。
+        // 在一次完整的回收之后，这个计数器被重置为0。
+        self.attach_count
+            .store(0, std::sync::atomic::Ordering::Relaxed);
+
+        self.progress.end();
+        swept_ids
+    }
+
+    /// 追踪列表是否为空。外部引用的释放无法被观察到，因此这是唯一可以在回收前确定没有垃圾的情况。
+    /// `dirty` 为 `false` 时无需加锁；追踪列表被确认为空时清除 `dirty`
+    fn tracked_is_empty(&mut self) -> bool {
+        if !*self.dirty.get_mut() {
+            return true;
+        }
+        let empty = self.gc_refs.get_mut().unwrap().is_empty();
+        if empty {
+            *self.dirty.get_mut() = false;
+        }
+        empty
+    }
+
+    /// 追踪列表为空时的回收：不扫描根对象、不分配标记表，只重置计数器并完成收尾工作
+    fn collect_empty(&mut self) {
+        self.emit(CollectionEvent::CollectionStarted);
+        self.last_collection_stats = CollectionStats::default();
+        self.attach_count
+            .store(0, std::sync::atomic::Ordering::Relaxed);
+        self.finish_collection(&[]);
+    }
+
+    /// 回收结束后的收尾工作：清理失效的冻结子图，并调用清除后回调
+    fn finish_collection(&mut self, swept_ids: &[u64]) {
+        self.last_collection = Instant::now();
+        if self.adaptive {
+            self.tune_percentage(swept_ids.len());
+        }
+        self.prune_frozen();
+        {
+            // 被回收对象的存活条件不再需要；id不会被复用，留下的条目只会占用内存
+            let mut liveness = self.liveness.lock().unwrap();
+            if !liveness.is_empty() {
+                for id in swept_ids {
+                    liveness.remove(id);
+                }
+            }
+        }
+        if self.null_dead_weaks && !swept_ids.is_empty() {
+            self.for_each(|r| r.as_ref().prune_dead_weaks());
+        }
+        if self.event_sink.is_some() {
+            let bytes = std::mem::size_of::<T>() + std::mem::size_of::<GCArc<T>>();
+            for &id in swept_ids {
+                self.emit(CollectionEvent::ObjectFreed { id, bytes });
+            }
+            self.emit(CollectionEvent::CollectionFinished(self.last_collection_stats));
+        }
+        for callback in self.post_sweep_callbacks.lock().unwrap().iter_mut() {
+            callback(swept_ids);
+        }
+    }
+
+    /// 向事件通道发送事件而不阻塞：通道已满时丢弃事件，接收端已断开时移除通道
+    fn emit(&mut self, event: CollectionEvent) {
+        let Some(sink) = &self.event_sink else {
+            return;
+        };
+        if let Err(TrySendError::Disconnected(_)) = sink.try_send(event) {
+            self.event_sink = None;
+        }
+    }
+
+    /// 以本次回收的效果更新被回收比例的移动平均，并据此调整百分比阈值：
+    /// 回收的垃圾很少时提高阈值以减少收益很小的回收，回收的垃圾很多时降低阈值以更早回收
+    fn tune_percentage(&mut self, swept: usize) {
+        const SMOOTHING: f64 = 0.3;
+        const LOW_RATIO: f64 = 0.1;
+        const HIGH_RATIO: f64 = 0.5;
+        const MIN_PERCENTAGE: usize = 5;
+        const MAX_PERCENTAGE: usize = 400;
+
+        let before = swept + self.object_count();
+        if before == 0 {
+            return;
+        }
+        let ratio = swept as f64 / before as f64;
+        self.garbage_ratio = SMOOTHING * ratio + (1.0 - SMOOTHING) * self.garbage_ratio;
+        let percentage = self.collection_percentage;
+        if self.garbage_ratio < LOW_RATIO {
+            self.collection_percentage = (percentage * 3 / 2)
+                .max(percentage + 1)
+                .min(MAX_PERCENTAGE.max(percentage));
+        } else if self.garbage_ratio > HIGH_RATIO {
+            self.collection_percentage = (percentage * 2 / 3).max(MIN_PERCENTAGE.min(percentage));
+        }
+    }
+
+    /// 设置接收回收事件的通道，None表示不再发送事件。回收开始时发送 `CollectionStarted`，
+    /// 结束时为每个被回收的对象发送 `ObjectFreed`，最后发送 `CollectionFinished`，适用于在其他线程中汇总或展示回收活动，
+    /// 而无需在回收过程中运行用户代码。使用有界通道（`mpsc::sync_channel`）且从不阻塞回收：
+    /// 通道已满时新事件被直接丢弃，因此接收端处理过慢时可能看到不完整的事件序列；接收端断开后通道被自动移除
+    pub fn set_event_sink(&mut self, sink: Option<SyncSender<CollectionEvent>>) {
+        self.event_sink = sink;
+    }
+
+    /// 注册一个清除后回调，每次回收完成并释放被回收的对象之后，以被回收对象的id（见 `GCArc::id`）调用
+    /// 适用于让依赖对象身份的外部弱引用表及时移除失效的条目，而无需轮询
+    /// 弱引用追踪模式下传入的是本次被清除的失效追踪项对应的id
+    pub fn on_post_sweep<F>(&mut self, callback: F)
+    where
+        F: FnMut(&[u64]) + Send + 'static,
+    {
+        self.post_sweep_callbacks
+            .lock()
+            .unwrap()
+            .push(Box::new(callback));
+    }
+
+    /// 预演一次回收：执行标记阶段，返回本次 `collect()` 将会回收的对象的弱引用，但不进行清除
+    /// 调用者可以先检查这些对象再决定是否真正执行回收；追踪状态和引用计数不会被修改
+    /// 弱引用追踪模式下返回已经失效、将被清除的追踪项
+    pub fn plan_collection(&self) -> Vec<GCArcWeak<T>> {
+        if self.weak_tracking {
+            return self
+                .weak_refs
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(r, _)| !r.is_valid())
+                .map(|(r, _)| r.clone())
+                .collect();
+        }
+
+        let refs = self.gc_refs.lock().unwrap();
+        let marked = self.mark(&refs, &mut CollectionStats::default());
+        refs.iter()
+            .filter(|r| {
+                let ptr = r.as_ref() as *const T as usize;
+                !*marked.get(&ptr).unwrap_or(&false)
+            })
+            .map(|r| r.as_weak())
+            .collect()
+    }
+
+    /// 冻结以 `root` 为根的子图：记录并缓存其当前可达的所有对象。
+    /// 之后的回收中，一旦根对象被标记为存活，整个子图都直接被视为存活而不再重新遍历；
+    /// 若根对象不可达，子图成员按常规方式处理。
+    /// 调用者需保证冻结期间子图不再改变（例如已加载完成的标准库），否则需先调用 `unfreeze`。
+    /// 冻结期间GC持有成员的弱引用，会计入 `weak_ref()` 计数。
+    pub fn freeze_subgraph(&mut self, root: &GCArc<T>) {
+        let mut members = Vec::new();
+        let mut member_keys = Vec::new();
+        let mut visited = rustc_hash::FxHashSet::default();
+        let mut queue = VecDeque::new();
+        queue.push_back(root.as_weak());
+        while let Some(current_weak) = queue.pop_front() {
+            let Some(current) = current_weak.upgrade_untracked() else {
+                continue;
+            };
+            let key = current.as_ref() as *const T as usize;
+            if !visited.insert(key) {
+                continue;
+            }
+            members.push(current_weak);
+            member_keys.push(key);
+            trace_children(current.as_ref(), &mut queue);
+        }
+
+        self.frozen.insert(
+            root.as_ref() as *const T as usize,
+            FrozenSubgraph {
+                members,
+                member_keys,
+            },
+        );
+    }
+
+    /// 解冻以 `root` 为根的子图，使其在之后的回收中重新被遍历，返回该子图是否曾被冻结
+    pub fn unfreeze(&mut self, root: &GCArc<T>) -> bool {
+        self.frozen
+            .remove(&(root.as_ref() as *const T as usize))
+            .is_some()
+    }
+
+    /// 移除根对象已被释放的冻结子图
+    fn prune_frozen(&mut self) {
+        self.frozen
+            .retain(|_, frozen| frozen.members.first().is_some_and(|root| root.is_valid()));
+    }
+
+    /// 弱引用追踪模式下的回收过程
+    /// GC不持有强引用，所有仍然存活的对象都必然被外部强引用持有，即都是根对象，
+    /// 因此无需标记，只需清除已经失效（无法升级）的追踪项
+    fn collect_weak_tracked(&mut self) {
+        self.emit(CollectionEvent::CollectionStarted);
+        let mut weak_refs = self.weak_refs.lock().unwrap();
+        let mut swept_ids = Vec::new();
+        weak_refs.retain(|(r, id)| {
+            let valid = r.is_valid();
+            if !valid {
+                swept_ids.push(*id);
+            }
+            valid
+        });
+        drop(weak_refs);
+        let removed = swept_ids.len();
+
+        let obj_size = std::mem::size_of::<T>() + std::mem::size_of::<GCArc<T>>();
+        self.allocated_memory
+            .fetch_sub(removed * obj_size, std::sync::atomic::Ordering::Relaxed);
+
+        self.attach_count
+            .store(0, std::sync::atomic::Ordering::Relaxed);
+
+        self.finish_collection(&swept_ids);
+    }
+
+    pub fn object_count(&self) -> usize {
+        if self.weak_tracking {
+            return self.weak_refs.lock().unwrap().len();
+        }
+        return self.gc_refs.lock().unwrap().len();
+    }
+
+    /// 记录所有被追踪对象的引用计数，用于通过 `HeapSnapshot::diff` 比较两个时刻之间的变化
+    /// 只读取计数，不修改堆；强引用计数包含GC自身持有的引用
+    /// 弱引用追踪模式下只记录仍然存活的对象
+    pub fn snapshot(&self) -> HeapSnapshot {
+        let mut counts = BTreeMap::new();
+        if self.weak_tracking {
+            for (r, id) in self.weak_refs.lock().unwrap().iter() {
+                if let Some(strong) = r.upgrade_untracked() {
+                    // 扣除本次升级产生的临时强引用
+                    counts.insert(*id, (strong.strong_ref() - 1, strong.weak_ref()));
+                }
+            }
+        } else {
+            for r in self.gc_refs.lock().unwrap().iter() {
+                counts.insert(r.id(), (r.strong_ref(), r.weak_ref()));
+            }
+        }
+        HeapSnapshot { counts }
+    }
+
+    /// 统计被追踪对象的大小分布，按 `GCTraceable::size_hint` 分桶计数
+    /// 键为桶的上界：大小被向上取整到2的幂，例如大小为24的对象计入键为32的桶
+    /// 弱引用追踪模式下只统计仍然存活的对象
+    pub fn size_histogram(&self) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+        let mut record = |value: &T| {
+            *histogram
+                .entry(value.size_hint().next_power_of_two())
+                .or_insert(0) += 1;
+        };
+        if self.weak_tracking {
+            for (r, _) in self.weak_refs.lock().unwrap().iter() {
+                if let Some(strong) = r.upgrade_untracked() {
+                    record(strong.as_ref());
+                }
+            }
+        } else {
+            for r in self.gc_refs.lock().unwrap().iter() {
+                record(r.as_ref());
+            }
+        }
+        histogram
+    }
+
+    /// 为对象创建一个软引用（见 `GCSoftRef`），对象应当被当前GC追踪
+    pub fn soft_ref(&self, gc_arc: &GCArc<T>) -> GCSoftRef<T> {
+        let alive = Arc::new(());
+        self.soft_refs
+            .lock()
+            .unwrap()
+            .push((gc_arc.as_weak(), Arc::downgrade(&alive)));
+        GCSoftRef {
+            target: gc_arc.as_weak(),
+            _alive: alive,
+        }
+    }
+
+    /// 结构去重：按 `key` 对被追踪的对象分组，每组保留第一个对象作为代表，
+    /// 通过 `GCTraceable::rewrite_edges` 将所有指向同组其他对象的边改写为指向代表，
+    /// 然后将重复的对象移出GC，返回被合并的对象数
+    ///
+    /// 注意：只适用于同组对象真正可以互换的场景（例如不可变的值对象图）。
+    /// `T` 必须为所有边实现 `rewrite_edges`，否则指向被合并对象的边会在其释放后失效；
+    /// 被固定的对象不会被合并；仍被外部持有的重复对象不会被释放，但也不再被GC追踪。
+    /// 被合并对象的移除与回收相同：发送回收事件、移除存活条件并调用清除后回调。弱引用追踪模式下不做任何处理
+    pub fn dedup_by<K, F>(&mut self, key: F) -> usize
+    where
+        K: Eq + Hash,
+        F: Fn(&T) -> K,
+    {
+        if self.weak_tracking {
+            return 0;
+        }
+
+        // 持有锁时只完成分组并移出重复的对象；`rewrite_edges` 由用户实现，在释放锁之后调用
+        let mut remap: FxHashMap<usize, GCArcWeak<T>> = FxHashMap::default();
+        let mut duplicates = Vec::new();
+        let mut swept_ids = Vec::new();
+        let survivors: Vec<GCArcWeak<T>> = {
+            let mut refs = self.gc_refs.lock().unwrap();
+            let mut representatives: HashMap<K, GCArcWeak<T>> = HashMap::new();
+            for r in refs.iter() {
+                match representatives.entry(key(r.as_ref())) {
+                    std::collections::hash_map::Entry::Occupied(entry) => {
+                        if !r.is_pinned() {
+                            remap.insert(r.as_wrapper_ptr() as usize, entry.get().clone());
+                        }
+                    }
+                    std::collections::hash_map::Entry::Vacant(entry) => {
+                        entry.insert(r.as_weak());
+                    }
+                }
+            }
+            if remap.is_empty() {
+                return 0;
+            }
+
+            let obj_size = std::mem::size_of::<T>() + std::mem::size_of::<GCArc<T>>();
+            let mut young = self.young.lock().unwrap();
+            let mut large = self.large.lock().unwrap();
+            refs.retain(|r| {
+                if !remap.contains_key(&(r.as_wrapper_ptr() as usize)) {
+                    return true;
+                }
+                r.inner().release_attachment();
+                self.allocated_memory
+                    .fetch_sub(obj_size, std::sync::atomic::Ordering::Relaxed);
+                young.remove(&r.id());
+                large.remove(&(r.as_wrapper_ptr() as usize));
+                swept_ids.push(r.id());
+                duplicates.push(r.clone());
+                false
+            });
+            refs.iter().map(|r| r.as_weak()).collect()
+        };
+        self.emit(CollectionEvent::CollectionStarted);
+
+        // 重复的对象在改写完成之前仍由 `duplicates` 持有，`remap` 中的地址不会被复用
+        let lookup = |edge: &GCArcWeak<T>| remap.get(&(edge.as_wrapper_ptr() as usize)).cloned();
+        for survivor in survivors.iter() {
+            if let Some(r) = survivor.upgrade_untracked() {
+                r.as_ref().rewrite_edges(&lookup);
+            }
+        }
+        drop(remap);
+
+        let resurrected = self.release_garbage(duplicates);
+        swept_ids.retain(|id| !resurrected.contains(id));
+        self.finish_collection(&swept_ids);
+        swept_ids.len()
+    }
+
+    /// 按 `key` 从对象值计算出的键对被追踪的对象分组计数，无需通过 `get_all` 复制整个对象集合
+    /// 弱引用追踪模式下只统计仍然存活的对象
+    pub fn object_count_by<K, F>(&self, key: F) -> HashMap<K, usize>
+    where
+        K: Eq + Hash,
+        F: Fn(&T) -> K,
+    {
+        let mut counts = HashMap::new();
+        if self.weak_tracking {
+            for (r, _) in self.weak_refs.lock().unwrap().iter() {
+                if let Some(strong) = r.upgrade_untracked() {
+                    *counts.entry(key(strong.as_ref())).or_insert(0) += 1;
+                }
+            }
+            return counts;
+        }
+        for r in self.gc_refs.lock().unwrap().iter() {
+            *counts.entry(key(r.as_ref())).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// 粗略统计外部代码持有的弱引用，返回 `(alive, dead)`，用于观察用户代码是否在泄漏弱引用
+    /// `alive` 为指向仍存活的被追踪对象的外部弱引用总数，即各对象的 `weak_ref()` 减去GC内部
+    /// （冻结子图、软引用和弱引用追踪列表）持有的弱引用数；
+    /// `dead` 为目标已被释放的追踪项数，只可能出现在弱引用追踪模式下。
+    /// 已被回收对象的外部弱引用无法统计，结果只适合作为趋势指标
+    pub fn live_weak_summary(&self) -> (usize, usize) {
+        let mut held: FxHashMap<usize, usize> = FxHashMap::default();
+        for subgraph in self.frozen.values() {
+            for member in subgraph.members.iter() {
+                *held.entry(member.as_wrapper_ptr() as usize).or_default() += 1;
+            }
+        }
+        for (target, _) in self.soft_refs.lock().unwrap().iter() {
+            *held.entry(target.as_wrapper_ptr() as usize).or_default() += 1;
+        }
+
+        let external = |r: &GCArc<T>, held: &FxHashMap<usize, usize>| {
+            let internal = held.get(&(r.as_wrapper_ptr() as usize)).copied().unwrap_or(0);
+            r.weak_ref().saturating_sub(internal)
+        };
+
+        if self.weak_tracking {
+            let weak_refs = self.weak_refs.lock().unwrap();
+            let mut alive = 0;
+            let mut dead = 0;
+            for (r, _) in weak_refs.iter() {
+                match r.upgrade_untracked() {
+                    // 追踪列表自身持有一个弱引用
+                    Some(r) => alive += external(&r, &held).saturating_sub(1),
+                    None => dead += 1,
+                }
+            }
+            return (alive, dead);
+        }
+
+        let refs = self.gc_refs.lock().unwrap();
+        let alive = refs.iter().map(|r| external(r, &held)).sum();
+        (alive, 0)
+    }
+
+    /// 统计当前被识别为根对象的数量（存在外部强引用、被固定或处于宽限期的对象），不执行回收
+    /// 可用于判断外部引用是否使过多对象保持存活
+    /// 弱引用追踪模式下不进行标记，始终返回0
+    pub fn num_roots(&self) -> usize {
+        if self.weak_tracking {
+            return 0;
+        }
+        let refs = self.gc_refs.lock().unwrap();
+        self.mark_roots(&refs).1.len()
+    }
+
+    /// 检查回收器的内部不变量，返回所有违反的条目，用于测试和调试
+    /// 检查项：每个被追踪对象的 `attached_gc_count` 至少为1、没有对象被重复追踪、
+    /// `allocated_memory` 与被追踪对象的大小之和一致、被追踪对象的标记位均已清除
+    /// 通过 `GCArcWeak::upgrade_and_mark` 手动设置后未清除的标记位也会被报告
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut violations = Vec::new();
+        let obj_size = std::mem::size_of::<T>() + std::mem::size_of::<GCArc<T>>();
+        let mut seen = rustc_hash::FxHashSet::default();
+
+        let count = if self.weak_tracking {
+            let weak_refs = self.weak_refs.lock().unwrap();
+            for (r, id) in weak_refs.iter() {
+                if !seen.insert(r.as_wrapper_ptr() as usize) {
+                    violations.push(format!("object {} is tracked more than once", id));
+                }
+            }
+            weak_refs.len()
+        } else {
+            let refs = self.gc_refs.lock().unwrap();
+            for r in refs.iter() {
+                if !seen.insert(r.as_wrapper_ptr() as usize) {
+                    violations.push(format!("object {} is tracked more than once", r.id()));
+                }
+                let attached = r
+                    .inner()
+                    .attached_gc_count
+                    .load(std::sync::atomic::Ordering::Relaxed);
+                if attached == 0 {
+                    violations.push(format!("object {} has attached_gc_count 0", r.id()));
+                }
+                if r.is_marked() {
+                    violations.push(format!("object {} is still marked", r.id()));
+                }
+            }
+            refs.len()
+        };
+
+        let allocated = self.allocated_memory();
+        if allocated != count * obj_size {
+            violations.push(format!(
+                "allocated_memory is {} bytes, expected {} bytes for {} objects",
+                allocated,
+                count * obj_size,
+                count
+            ));
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// 修复 `validate` 能发现的簿记错误，返回修复的异常数，用于从损坏的状态中恢复而不是panic
+    /// 移除指向已释放对象的追踪项（正常情况下只可能出现在弱引用追踪模式下，其余模式中GC持有强引用）、
+    /// 重复追踪的对象，清除残留的标记位，将为0的 `attached_gc_count` 恢复为1，最后按被追踪对象数重新计算 `allocated_memory`。
+    /// 正常运行时不需要调用
+    pub fn prune(&mut self) -> usize {
+        let obj_size = std::mem::size_of::<T>() + std::mem::size_of::<GCArc<T>>();
+        let mut fixed = 0;
+        let mut seen = rustc_hash::FxHashSet::default();
+
+        let count = if self.weak_tracking {
+            let mut weak_refs = self.weak_refs.lock().unwrap();
+            weak_refs.retain(|(r, _)| {
+                let keep = r.is_valid() && seen.insert(r.as_wrapper_ptr() as usize);
+                if !keep {
+                    fixed += 1;
+                }
+                keep
+            });
+            weak_refs.len()
+        } else {
+            let mut refs = self.gc_refs.lock().unwrap();
+            let mut duplicates = Vec::new();
+            refs.retain(|r| {
+                if seen.insert(r.as_wrapper_ptr() as usize) {
+                    return true;
+                }
+                duplicates.push(r.clone());
+                false
+            });
+            for duplicate in duplicates {
+                // 每个重复项都持有一次attach，保证计数不低于1再释放
+                if duplicate
+                    .inner()
+                    .attached_gc_count
+                    .load(std::sync::atomic::Ordering::Relaxed)
+                    > 1
+                {
+                    duplicate.inner().release_attachment();
+                }
+                fixed += 1;
+            }
+            for r in refs.iter() {
+                let attached = &r.inner().attached_gc_count;
+                if attached.load(std::sync::atomic::Ordering::Relaxed) == 0 {
+                    attached.store(1, std::sync::atomic::Ordering::Relaxed);
+                    fixed += 1;
+                }
+                if r.inner()
+                    .marked
+                    .swap(false, std::sync::atomic::Ordering::Relaxed)
+                {
+                    fixed += 1;
+                }
+            }
+            refs.len()
+        };
+
+        if self.allocated_memory() != count * obj_size {
+            self.allocated_memory
+                .store(count * obj_size, std::sync::atomic::Ordering::Relaxed);
+            fixed += 1;
+        }
+        fixed
+    }
+
+    /// 返回所有被追踪对象的强引用，弱引用追踪模式下只返回仍然存活的对象
+    /// 注意：结果持有强引用，在它被丢弃之前其中的对象都会被视为根对象，它们引用的对象也不会被回收。
+    /// 只需遍历时应使用 `for_each`；需要在回收统计中发现未丢弃的结果时使用 `get_all_tracked`
+    pub fn get_all(&self) -> Vec<GCArc<T>> {
+        if self.weak_tracking {
+            return self
+                .weak_refs
+                .lock()
+                .unwrap()
+                .iter()
+                .filter_map(|(r, _)| r.upgrade_untracked())
+                .collect();
+        }
+        self.gc_refs.lock().unwrap().clone()
+    }
+
+    /// 与 `get_all` 相同，但结果在被丢弃之前计入 `CollectionStats::outstanding_get_all`，
+    /// 用于发现意外长期持有的结果使对象无法被回收的情况
+    pub fn get_all_tracked(&self) -> TrackedObjects<T> {
+        TrackedObjects::new(self.get_all(), self.outstanding_get_all.clone())
+    }
+
+    /// 在持有追踪列表锁的情况下依次访问所有被追踪的对象，遍历期间无法进行回收
+    /// 这是遍历被追踪对象的推荐方式：不分配内存，也不产生额外的强引用；需要弱引用时在 `f` 中调用 `as_weak`。
+    /// `f` 中不能调用同一个GC的方法，否则会死锁。
+    /// 弱引用追踪模式下GC只持有弱引用，每个仍然存活的对象在被访问期间会被临时升级，已释放的对象被跳过
+    pub fn for_each<F>(&self, mut f: F)
+    where
+        F: FnMut(&GCArc<T>),
+    {
+        if self.weak_tracking {
+            for (r, _) in self.weak_refs.lock().unwrap().iter() {
+                if let Some(r) = r.upgrade_untracked() {
+                    f(&r);
+                }
+            }
+            return;
+        }
+        self.gc_refs.lock().unwrap().iter().for_each(f);
+    }
+
+    /// 类似 `Vec::drain`，移出并逐个返回所有被追踪的对象，将所有权交给调用者
+    /// 每返回一个对象都会相应更新 `attached_gc_count` 和内存估算，
+    /// 迭代器被提前丢弃时剩余的对象同样会被移出追踪
+    /// 弱引用追踪模式下只返回仍然存活的对象，已被释放的对象在迭代经过时同样从内存估算中扣除
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        self.attach_count
+            .store(0, std::sync::atomic::Ordering::Relaxed);
+
+        let weak_refs = std::mem::take(&mut *self.weak_refs.lock().unwrap());
+        let refs = std::mem::take(&mut *self.gc_refs.lock().unwrap());
+        self.young.lock().unwrap().clear();
+        self.large.lock().unwrap().clear();
+        self.liveness.lock().unwrap().clear();
+        Drain {
+            gc: self,
+            refs: refs.into_iter(),
+            weak_refs: weak_refs.into_iter(),
+        }
+    }
+
+    /// 创建一个新对象并添加到GC中
+    /// 与 `attach` 相同，不检查硬性内存上限；需要在超过上限时失败的调用者应使用 `try_create`
+    pub fn create(&mut self, obj: T) -> GCArc<T> {
+        let gc_arc = GCArc::new(obj);
+        self.attach(&gc_arc);
+        gc_arc
+    }
+
+    /// 创建一个新对象并通过 `try_attach` 添加到GC中，超过硬性内存上限时返回错误
+    pub fn try_create(&mut self, obj: T) -> Result<GCArc<T>, GCError> {
+        let gc_arc = GCArc::new(obj);
+        self.try_attach(&gc_arc)?;
+        Ok(gc_arc)
+    }
+
+    /// 获取当前分配的内存估算值（字节）
+    pub fn allocated_memory(&self) -> usize {
+        self.allocated_memory.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// 获取累计通过此GC分配的内存估算值（字节），每次attach都会增加且从不减少
+    /// 与 `allocated_memory()` 结合可以同时得到当前占用和分配速率
+    pub fn total_allocated_bytes(&self) -> usize {
+        self.total_allocated.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// 设置内存阈值，None表示禁用内存阈值触发
+    pub fn set_memory_threshold(&mut self, threshold: Option<usize>) {
+        self.memory_threshold = threshold;
+    }
+
+    /// 获取当前内存阈值
+    pub fn memory_threshold(&self) -> Option<usize> {
+        self.memory_threshold
+    }
+
+    /// 设置硬性内存上限，None表示不限制
+    /// 只有 `try_attach`/`try_create` 会检查此上限，`attach`/`create` 不受影响
+    pub fn set_hard_limit(&mut self, limit: Option<usize>) {
+        self.hard_limit = limit;
+    }
+
+    /// 获取当前硬性内存上限
+    pub fn hard_limit(&self) -> Option<usize> {
+        self.hard_limit
+    }
+
+    /// 导出启发式回收所用的计数器（见 `GCState`）
+    pub fn export_state(&self) -> GCState {
+        GCState {
+            attach_count: self
+                .attach_count
+                .load(std::sync::atomic::Ordering::Relaxed),
+            allocated_memory: self.allocated_memory(),
+            total_allocated: self.total_allocated_bytes(),
+        }
+    }
+
+    /// 用 `state` 覆盖 `attach_count` 和累计分配量，避免恢复后回收时机被重置
+    /// 由于覆盖而非累加，应当在重新attach恢复的对象之后调用，此时对象的attach不会被重复计数。
+    /// 当前分配量不被导入：它由attach和detach增减，被覆盖后与实际被追踪的对象不一致，detach全部对象后也无法归零
+    pub fn import_state(&mut self, state: GCState) {
+        self.attach_count
+            .store(state.attach_count, std::sync::atomic::Ordering::Relaxed);
+        self.total_allocated
+            .store(state.total_allocated, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// 获取最近一次回收的统计信息，可用于发现过深或过宽的对象图
+    /// 弱引用追踪模式下不进行标记，统计信息始终为零
+    pub fn last_collection_stats(&self) -> CollectionStats {
+        self.last_collection_stats
+    }
+
+    /// 获取当前回收已标记和已清除的对象数，没有正在进行的回收时返回 `None`
+    /// 弱引用追踪模式下不进行标记，始终返回 `None`
+    pub fn collection_progress(&self) -> Option<(usize, usize)> {
+        self.progress.get()
+    }
+
+    /// 获取回收进度计数器的共享句柄
+    /// `collect` 需要独占GC，因此其他线程应持有此句柄来轮询回收进度
+    pub fn progress_handle(&self) -> Arc<CollectionProgress> {
+        self.progress.clone()
+    }
+
+    /// 为追踪列表预留至少可再容纳 `additional` 个对象的空间，避免之后的attach触发扩容
+    pub fn reserve(&mut self, additional: usize) {
+        if self.weak_tracking {
+            self.weak_refs.lock().unwrap().reserve(additional);
+        } else {
+            self.gc_refs.lock().unwrap().reserve(additional);
+        }
+    }
+
+    /// 追踪列表在不扩容的情况下还能容纳的对象数
+    pub fn spare_capacity(&self) -> usize {
+        if self.weak_tracking {
+            let weak_refs = self.weak_refs.lock().unwrap();
+            return weak_refs.capacity() - weak_refs.len();
+        }
+        let gc_refs = self.gc_refs.lock().unwrap();
+        gc_refs.capacity() - gc_refs.len()
+    }
+
+    /// 粗略估算碎片化程度，返回0到1之间的值，越大表示浪费越多
+    /// 由两部分组合而成：追踪列表的空闲比例 `1 - len / capacity`，以及对象大小（`GCTraceable::size_hint`）的离散程度
+    /// `cv / (1 + cv)`（`cv` 为变异系数），结果为 `1 - (1 - 空闲比例) * (1 - 离散程度)`。
+    /// 只是启发式的指标：空闲比例高说明追踪列表占用了多余的内存，大小差异大的对象更容易造成分配器碎片
+    pub fn fragmentation_estimate(&self) -> f64 {
+        let mut sizes = Vec::new();
+        let (len, capacity) = if self.weak_tracking {
+            let weak_refs = self.weak_refs.lock().unwrap();
+            for (r, _) in weak_refs.iter() {
+                if let Some(r) = r.upgrade_untracked() {
+                    sizes.push(r.as_ref().size_hint() as f64);
+                }
+            }
+            (weak_refs.len(), weak_refs.capacity())
+        } else {
+            let gc_refs = self.gc_refs.lock().unwrap();
+            sizes.extend(gc_refs.iter().map(|r| r.as_ref().size_hint() as f64));
+            (gc_refs.len(), gc_refs.capacity())
+        };
+        if capacity == 0 {
+            return 0.0;
+        }
+        let slack = 1.0 - len as f64 / capacity as f64;
+
+        let dispersion = if sizes.is_empty() {
+            0.0
+        } else {
+            let mean = sizes.iter().sum::<f64>() / sizes.len() as f64;
+            let variance =
+                sizes.iter().map(|size| (size - mean).powi(2)).sum::<f64>() / sizes.len() as f64;
+            let cv = if mean > 0.0 { variance.sqrt() / mean } else { 0.0 };
+            cv / (1.0 + cv)
+        };
+        1.0 - (1.0 - slack) * (1.0 - dispersion)
+    }
+
+    /// 设置追踪列表的扩容策略：`Some(n)` 表示列表已满时每次只扩容 `n` 个位置（线性增长），
+    /// 使单次扩容的开销可预测；`None`（默认）使用 `Vec` 的倍增策略。`n` 为0时按1处理
+    pub fn set_growth_increment(&mut self, increment: Option<usize>) {
+        self.growth_increment = increment.map(|n| n.max(1));
+    }
+
+    /// 获取追踪列表的扩容增量
+    pub fn growth_increment(&self) -> Option<usize> {
+        self.growth_increment
+    }
+
+    /// 设置新对象的宽限期：新attach的对象在接下来的 `cycles` 次回收中被视为根对象，
+    /// 避免自动回收在对象图尚未构建完成时回收暂时没有被引用的新对象。默认为0，即没有宽限期
+    /// 修改只影响之后attach的对象
+    pub fn set_grace_period(&mut self, cycles: u32) {
+        self.grace_period = cycles;
+    }
+
+    /// 获取新对象的宽限期
+    pub fn grace_period(&self) -> u32 {
+        self.grace_period
+    }
+
+    /// 设置是否在每次释放了对象的回收之后，对所有存活对象调用 `GCTraceable::prune_dead_weaks`，
+    /// 让它们丢弃指向已释放对象的弱引用边，避免失效的弱引用占用内存并导致反复的升级失败。默认关闭
+    pub fn set_null_dead_weaks(&mut self, enabled: bool) {
+        self.null_dead_weaks = enabled;
+    }
+
+    /// 是否在回收后清理存活对象中失效的弱引用边
+    pub fn null_dead_weaks(&self) -> bool {
+        self.null_dead_weaks
+    }
+
+    /// 设置自动回收的最小收益：自上次回收以来attach的对象少于 `count` 个时推迟自动回收，
+    /// 即使内存阈值或百分比阈值已经达到，避免突发分配时频繁进行收益很小的回收。None表示不限制
+    /// 新attach的对象数只是对新增垃圾量的估算；超过 `set_max_interval` 设置的最长间隔时仍会回收
+    pub fn set_min_garbage(&mut self, count: Option<usize>) {
+        self.min_garbage = count;
+    }
+
+    /// 获取自动回收的最小收益
+    pub fn min_garbage(&self) -> Option<usize> {
+        self.min_garbage
+    }
+
+    /// 设置大对象阈值：`GCTraceable::size_hint` 超过 `bytes` 的新对象进入大对象空间，
+    /// 只在 `collect_major` 中被回收，减少由少数长期存活的大对象主导的堆上每次回收的开销。None表示禁用
+    /// 修改只影响之后attach的对象
+    pub fn set_large_object_threshold(&mut self, bytes: Option<usize>) {
+        self.large_object_threshold = bytes;
+    }
+
+    /// 获取大对象阈值
+    pub fn large_object_threshold(&self) -> Option<usize> {
+        self.large_object_threshold
+    }
+
+    /// 获取当前的百分比阈值，启用 `set_adaptive` 时为自动调整后的值
+    pub fn collection_percentage(&self) -> usize {
+        self.collection_percentage
+    }
+
+    /// 启用或关闭百分比阈值的自动调整：每次回收后以被回收对象所占比例的指数加权移动平均调整阈值，
+    /// 近期回收的垃圾很少时提高阈值（更少回收），很多时降低阈值（更早回收），调整范围为5%到400%
+    /// 初始值为创建GC时设置的百分比；关闭后阈值保持在当前值
+    pub fn set_adaptive(&mut self, adaptive: bool) {
+        self.adaptive = adaptive;
+        self.garbage_ratio = 0.0;
+    }
+
+    /// 是否启用了百分比阈值的自动调整
+    pub fn is_adaptive(&self) -> bool {
+        self.adaptive
+    }
+
+    /// 设置两次自动回收之间的最短间隔，间隔内即使达到其他触发条件也不自动回收，None表示不限制
+    /// 用于防止短时间内大量分配引起的频繁回收；显式调用 `collect()` 不受影响
+    pub fn set_min_interval(&mut self, interval: Option<Duration>) {
+        self.min_interval = interval;
+    }
+
+    /// 获取两次自动回收之间的最短间隔
+    pub fn min_interval(&self) -> Option<Duration> {
+        self.min_interval
+    }
+
+    /// 设置两次自动回收之间的最长间隔，距上次回收超过此间隔后，下一次attach无论分配量多少都会触发回收，None表示不限制
+    pub fn set_max_interval(&mut self, interval: Option<Duration>) {
+        self.max_interval = interval;
+    }
+
+    /// 获取两次自动回收之间的最长间隔
+    pub fn max_interval(&self) -> Option<Duration> {
+        self.max_interval
+    }
+
+    /// 暂停自动回收，用于对延迟敏感的临界区：暂停期间 `attach` 不会触发回收，显式调用 `collect()` 不受影响
+    /// 可以嵌套调用，每次 `pause` 需要对应一次 `resume`
+    pub fn pause(&mut self) {
+        self.pause_depth += 1;
+    }
+
+    /// 结束一层暂停。最外层暂停结束时，若启发式条件已满足（暂停期间本应触发的回收），
+    /// 且 `run_deferred` 为true，则立即进行一次回收
+    pub fn resume(&mut self, run_deferred: bool) {
+        self.pause_depth = self.pause_depth.saturating_sub(1);
+        if run_deferred && self.auto_collect && self.should_collect() {
+            self.collect();
+        }
+    }
+
+    /// 自动回收当前是否处于暂停状态
+    pub fn is_paused(&self) -> bool {
+        self.pause_depth > 0
+    }
+
+    /// 设置是否允许attach时自动触发回收，默认为true
+    /// 关闭后只有显式调用 `collect()` 才会进行回收，适用于测试等需要确定回收时机的场景
+    pub fn set_auto_collect(&mut self, enabled: bool) {
+        self.auto_collect = enabled;
+    }
+
+    /// 获取当前是否允许自动回收
+    pub fn auto_collect(&self) -> bool {
+        self.auto_collect
+    }    fn should_collect(&self) -> bool {
+        if self.pause_depth > 0 {
+            return false;
+        }
+        let current_count = self.object_count();
+        let attach_count = self.attach_count.load(std::sync::atomic::Ordering::Relaxed);
+        let current_memory = self.allocated_memory.load(std::sync::atomic::Ordering::Relaxed);
+
+        if current_count == 0 {
+            return false;
+        }
+
+        // 检查时间间隔：最短间隔优先于其他所有触发条件
+        let elapsed = self.last_collection.elapsed();
+        if self.min_interval.is_some_and(|min| elapsed < min) {
+            return false;
+        }
+        if self.max_interval.is_some_and(|max| elapsed >= max) {
+            return true;
+        }
+
+        // 自上次回收以来新attach的对象太少时，回收的收益不足以抵消开销，推迟回收
+        if self.min_garbage.is_some_and(|min| attach_count < min) {
+            return false;
+        }
+
+        // 检查内存阈值
+        if let Some(memory_threshold) = self.memory_threshold {
+            if current_memory >= memory_threshold {
+                return true;
+            }
+        }
+
+        // 检查百分比阈值：当attach次数超过当前对象数的指定百分比时触发回收
+        let threshold = (current_count * self.collection_percentage) / 100;
+        attach_count >= threshold.max(1) // 至少1次attach才触发
+    }
+}
+
+thread_local! {
+    /// 当前线程的默认GC，按对象类型区分，值为 `Arc<Mutex<GC<T>>>`
+    static CURRENT_GC: RefCell<FxHashMap<TypeId, Box<dyn Any>>> = RefCell::new(FxHashMap::default());
+}
+
+/// 默认GC的便捷层：为只使用一个GC的程序省去到处传递 `&mut GC` 的麻烦
+///
+/// 注意事项：
+/// - 默认GC是线程局部的，只对设置它的线程生效，其他线程需要各自设置；
+///   线程退出时其默认GC的句柄随之释放，但只要仍有其他 `Arc` 句柄，GC本身就不会被销毁。
+/// - `GCArc::new_tracked` 需要锁住默认GC，因此不能在该GC的回收过程中（例如对象的 `Drop` 中）调用，否则会死锁。
+impl<T> GC<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    /// 将 `gc` 设为当前线程中类型 `T` 的默认GC，返回之前的默认GC
+    pub fn set_current(gc: Arc<Mutex<GC<T>>>) -> Option<Arc<Mutex<GC<T>>>> {
+        CURRENT_GC.with(|current| {
+            current
+                .borrow_mut()
+                .insert(TypeId::of::<T>(), Box::new(gc))
+                .and_then(|previous| previous.downcast().ok())
+                .map(|previous| *previous)
+        })
+    }
+
+    /// 清除当前线程中类型 `T` 的默认GC，返回被清除的默认GC
+    pub fn clear_current() -> Option<Arc<Mutex<GC<T>>>> {
+        CURRENT_GC.with(|current| {
+            current
+                .borrow_mut()
+                .remove(&TypeId::of::<T>())
+                .and_then(|previous| previous.downcast().ok())
+                .map(|previous| *previous)
+        })
+    }
+
+    /// 获取当前线程中类型 `T` 的默认GC
+    pub fn current() -> Option<Arc<Mutex<GC<T>>>> {
+        CURRENT_GC.with(|current| {
+            current
+                .borrow()
+                .get(&TypeId::of::<T>())
+                .and_then(|gc| gc.downcast_ref::<Arc<Mutex<GC<T>>>>())
+                .cloned()
+        })
+    }
+
+    /// 在 `f` 执行期间将 `gc` 设为默认GC，结束后（包括 `f` panic时）恢复之前的默认GC
+    pub fn with_current<R>(gc: Arc<Mutex<GC<T>>>, f: impl FnOnce() -> R) -> R {
+        struct Restore<T: GCTraceable<T> + 'static>(Option<Arc<Mutex<GC<T>>>>);
+        impl<T: GCTraceable<T> + 'static> Drop for Restore<T> {
+            fn drop(&mut self) {
+                match self.0.take() {
+                    Some(previous) => {
+                        GC::set_current(previous);
+                    }
+                    None => {
+                        GC::<T>::clear_current();
+                    }
+                }
+            }
+        }
+
+        let _restore = Restore(Self::set_current(gc));
+        f()
+    }
+}
+
+impl<T> GCArc<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    /// 创建一个新对象并添加到当前线程的默认GC中（见 `GC::set_current`）
+    /// 没有设置默认GC时panic
+    pub fn new_tracked(obj: T) -> GCArc<T> {
+        let gc = GC::<T>::current().expect("no current GC is set for this type on this thread");
+        let gc_arc = GCArc::new(obj);
+        gc.lock().unwrap().attach(&gc_arc);
+        gc_arc
+    }
+}
+
+/// 判断从 `from` 出发能否到达 `to` 指向的对象，用于排查对象为何没有被回收
+/// 使用 `GCTraceable::collect` 进行广度优先搜索，最多访问 `max_visits` 个对象，
+/// 超过上限仍未找到时返回 `false`，以避免在巨大的对象图上失控
+pub fn reachable<T>(from: &GCArc<T>, to: &GCArcWeak<T>, max_visits: usize) -> bool
+where
+    T: GCTraceable<T> + 'static,
+{
+    let target = to.as_wrapper_ptr();
+    let mut visited = rustc_hash::FxHashSet::default();
+    let mut queue = VecDeque::new();
+    queue.push_back(from.as_weak());
+    while let Some(current_weak) = queue.pop_front() {
+        if current_weak.as_wrapper_ptr() == target {
+            return true;
+        }
+        if visited.len() >= max_visits {
+            return false;
+        }
+        let Some(current) = current_weak.upgrade_untracked() else {
+            continue;
+        };
+        if !visited.insert(current.as_wrapper_ptr()) {
+            continue;
+        }
+        trace_children(current.as_ref(), &mut queue);
+    }
+    false
+}
+
+/// 根对象枚举器，将根对象的枚举与回收器本身分离，见 `GC::collect_with_scanner`
+pub trait RootScanner<T: GCTraceable<T> + 'static> {
+    /// 将所有根对象的弱引用加入 `sink`，`tracked` 为当前被追踪的对象
+    /// 每次回收在确认追踪列表非空后、持有追踪列表锁期间调用一次，因此不能调用 `gc` 上需要该锁的方法（如 `object_count`）
+    fn scan_roots(&self, gc: &GC<T>, tracked: &[GCArc<T>], sink: &mut VecDeque<GCArcWeak<T>>);
+}
+
+/// 与 `collect()` 的根对象判断规则相同的枚举器：存在GC之外的强引用、被固定、处于宽限期、位于大对象空间、
+/// 被未失效的软引用指向或存活条件成立的对象是根对象，并扣除对象之间的强引用边
+/// 根对象在每次 `scan_roots` 时按当时的状态重新判断，同一个扫描器可以用于任意多次回收；
+/// 也可以在自定义扫描器中调用它，在默认的根对象之外再报告其他根对象
+pub struct RefcountRootScanner;
+
+impl<T> RootScanner<T> for RefcountRootScanner
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn scan_roots(&self, gc: &GC<T>, tracked: &[GCArc<T>], sink: &mut VecDeque<GCArcWeak<T>>) {
+        let (_, roots) = gc.mark_roots(tracked);
+        sink.extend(roots.into_iter().map(|index| tracked[index].as_weak()));
+    }
+}
+
+/// `GC::get_all_tracked` 返回的被追踪对象列表，可以像切片一样使用
+/// 在被丢弃之前计入 `CollectionStats::outstanding_get_all`；通过 `into_vec` 取出后不再计入
+pub struct TrackedObjects<T: GCTraceable<T> + 'static> {
+    refs: Vec<GCArc<T>>,
+    outstanding: Arc<AtomicUsize>,
+}
+
+impl<T> TrackedObjects<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn new(refs: Vec<GCArc<T>>, outstanding: Arc<AtomicUsize>) -> Self {
+        outstanding.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        Self { refs, outstanding }
+    }
+
+    /// 取出其中的强引用
+    pub fn into_vec(mut self) -> Vec<GCArc<T>> {
+        std::mem::take(&mut self.refs)
+    }
+}
+
+impl<T> std::ops::Deref for TrackedObjects<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    type Target = [GCArc<T>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.refs
+    }
+}
+
+impl<'a, T> IntoIterator for &'a TrackedObjects<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    type Item = &'a GCArc<T>;
+    type IntoIter = std::slice::Iter<'a, GCArc<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.refs.iter()
+    }
+}
+
+impl<T> IntoIterator for TrackedObjects<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    type Item = GCArc<T>;
+    type IntoIter = std::vec::IntoIter<GCArc<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.into_vec().into_iter()
+    }
+}
+
+impl<T> From<TrackedObjects<T>> for Vec<GCArc<T>>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn from(tracked: TrackedObjects<T>) -> Self {
+        tracked.into_vec()
+    }
+}
+
+/// 克隆得到的列表同样计入未丢弃的结果数
+impl<T> Clone for TrackedObjects<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn clone(&self) -> Self {
+        Self::new(self.refs.clone(), self.outstanding.clone())
+    }
+}
+
+impl<T> std::fmt::Debug for TrackedObjects<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.refs.iter().map(|r| r.id()))
+            .finish()
+    }
+}
+
+impl<T> Drop for TrackedObjects<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn drop(&mut self) {
+        self.outstanding
+            .fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+    }
+}
+
+/// `GC::attach_scoped` 返回的守卫，被丢弃时将对象移出GC追踪
+pub struct ScopedAttach<'a, T: GCTraceable<T> + 'static> {
+    gc: &'a mut GC<T>,
+    gc_arc: GCArc<T>,
+}
+
+impl<T> ScopedAttach<'_, T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    /// 获取被临时追踪的对象
+    pub fn arc(&self) -> &GCArc<T> {
+        &self.gc_arc
+    }
+}
+
+impl<T> std::ops::Deref for ScopedAttach<'_, T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    type Target = GC<T>;
+
+    fn deref(&self) -> &GC<T> {
+        self.gc
+    }
+}
+
+impl<T> std::ops::DerefMut for ScopedAttach<'_, T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn deref_mut(&mut self) -> &mut GC<T> {
+        self.gc
+    }
+}
+
+impl<T> Drop for ScopedAttach<'_, T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn drop(&mut self) {
+        // 对象可能已经在作用域内被手动detach，此时不做任何事
+        self.gc.detach(&self.gc_arc);
+    }
+}
+
+/// `GC::drain` 返回的迭代器
+pub struct Drain<'a, T: GCTraceable<T> + 'static> {
+    gc: &'a GC<T>,
+    refs: std::vec::IntoIter<GCArc<T>>,
+    weak_refs: std::vec::IntoIter<(GCArcWeak<T>, u64)>, // 弱引用追踪模式下被移出的条目
+}
+
+impl<T> Iterator for Drain<'_, T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    type Item = GCArc<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let obj_size = std::mem::size_of::<T>() + std::mem::size_of::<GCArc<T>>();
+        if let Some(gc_arc) = self.refs.next() {
+            // 对象被移出GC，减少持有的 GC 实例数和内存计数
+            gc_arc.inner().release_attachment();
+            self.gc
+                .allocated_memory
+                .fetch_sub(obj_size, std::sync::atomic::Ordering::Relaxed);
+            return Some(gc_arc);
+        }
+
+        // 弱引用追踪模式下每个条目在attach时都计入了内存估算，无论对象是否存活都在经过时扣除
+        for (weak, _) in self.weak_refs.by_ref() {
+            self.gc
+                .allocated_memory
+                .fetch_sub(obj_size, std::sync::atomic::Ordering::Relaxed);
+            if let Some(gc_arc) = weak.upgrade_untracked() {
+                return Some(gc_arc);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (low, high) = self.refs.size_hint();
+        (low, high.map(|high| high + self.weak_refs.len()))
+    }
+}
+
+impl<T> Drop for Drain<'_, T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    fn drop(&mut self) {
+        // 移出剩余未被迭代的对象，保证计数正确
+        self.for_each(drop);
+    }
+}
+
+impl<T> FromIterator<GCArc<T>> for GC<T>
+where
+    T: GCTraceable<T> + 'static,
+{
+    /// 使用默认配置创建GC，并批量添加迭代器中的所有对象
+    fn from_iter<I: IntoIterator<Item = GCArc<T>>>(iter: I) -> Self {
+        let mut gc = GC::new();
+        gc.attach_many(iter);
+        gc
+    }
+}
+
+impl<T> Drop for GC<T>
+where
+    T: GCTraceable<T> + 'static,
+{    fn drop(&mut self) {
+        // 弱引用追踪模式下GC不持有对象，只需丢弃弱引用。
+        let obj_size = std::mem::size_of::<T>() + std::mem::size_of::<GCArc<T>>();
+        let weak_count = self.weak_refs.lock().unwrap().drain(..).count();
+        self.allocated_memory
+            .fetch_sub(weak_count * obj_size, std::sync::atomic::Ordering::Relaxed);
+
+        // 在垃圾回收器被销毁时，清理所有跟踪的对象。
+        // 这将触发所有对象的 `Drop` 实现。
+        let mut refs = self.gc_refs.lock().unwrap();
+        for gc_arc in refs.drain(..) {
+            // 减少 `attached_gc_count`，表示该对象不再被垃圾回收器跟踪。
+            gc_arc.inner().release_attachment();
+            
+            // 从内存计数中减去对象大小
+            let obj_size = std::mem::size_of::<T>() + std::mem::size_of::<GCArc<T>>();
+            self.allocated_memory
+                .fetch_sub(obj_size, std::sync::atomic::Ordering::Relaxed);
+                
+            // 直接调用 `drop` 方法，确保所有对象都被正确释放。
+            // 这将触发每个对象的 `Drop` 实现。
+            drop(gc_arc);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use crate::{arc::GCArcWeak, traceable::GCTraceable};
+
+    struct TestObject {
+        value: Option<GCArcWeak<TestObjectCell>>,
+    }
+
+    impl GCTraceable<TestObjectCell> for TestObject {
+        fn collect(&self, queue: &mut VecDeque<GCArcWeak<TestObjectCell>>) {
+            if let Some(ref weak_ref) = self.value {
+                queue.push_back(weak_ref.clone());
+            }
+        }
+    }
+
+    impl Drop for TestObject {
+        fn drop(&mut self) {
+            println!("Dropping TestObject: address={:p}", self);
+        }
+    }
+
+    struct TestObjectCell(RefCell<TestObject>);
+    impl GCTraceable<TestObjectCell> for TestObjectCell {
+        fn collect(&self, queue: &mut VecDeque<GCArcWeak<TestObjectCell>>) {
+            if let Ok(obj) = self.0.try_borrow() {
+                if let Some(ref weak_ref) = obj.value {
+                    queue.push_back(weak_ref.clone());
+                }
+            }
+        }
+    }
+    impl Drop for TestObjectCell {
+        fn drop(&mut self) {
+            println!("Dropping TestObjectCell: address={:p}", self);
+        }
+    }
+
+    #[test]
+    fn test_gc() {
+        let mut gc: GC<TestObjectCell> = GC::new_with_percentage(20);
+        {
+            let obj1 = gc.create(TestObjectCell {
+                0: RefCell::new(TestObject { value: None }),
+            });
+            let weak_ref = obj1.as_weak();
+            match obj1.as_ref().0.try_borrow_mut() {
+                Ok(mut obj) => {
+                    obj.value = Some(weak_ref);
+                }
+                Err(_) => {
+                    panic!("Failed to borrow TestObjectCell mutably");
+                }
+            }
+            print!("GC object count before collection: {}\n", gc.object_count());
+        }
+        gc.collect();
+        println!("GC completed, all objects should be dropped now.");
+    }
+
+    #[test]
+    fn test_memory_threshold_gc() {
+        // 使用较小的内存阈值（1KB）来测试内存触发
+        let mut gc: GC<TestObjectCell> = GC::new_with_memory_threshold(1024);
+        
+        println!("Initial allocated memory: {} bytes", gc.allocated_memory());
+        
+        // 创建多个对象直到触发内存阈值
+        let mut objects = Vec::new();
+        for i in 0..50 {
+            let obj = gc.create(TestObjectCell {
+                0: RefCell::new(TestObject { value: None }),
+            });
+            objects.push(obj);
+            
+            println!("After creating object {}: allocated={} bytes, object_count={}", 
+                    i + 1, gc.allocated_memory(), gc.object_count());
+            
+            if gc.allocated_memory() > 1024 {
+                break;
+            }
+        }
+        
+        println!("Before collection: allocated={} bytes, object_count={}", 
+                gc.allocated_memory(), gc.object_count());
+        
+        // 释放引用，让对象变成垃圾
+        objects.clear();
+        
+        // 手动触发回收
+        gc.collect();
+        
+        println!("After collection: allocated={} bytes, object_count={}", 
+                gc.allocated_memory(), gc.object_count());
+    }
+
+    #[test]
+    fn test_combined_thresholds_gc() {
+        // 测试同时使用百分比和内存阈值
+        let mut gc: GC<TestObjectCell> = GC::new_with_thresholds(50, 2048); // 50%或2KB
+        
+        println!("Testing combined thresholds: 50% or 2KB");
+        
+        let obj1 = gc.create(TestObjectCell {
+            0: RefCell::new(TestObject { value: None }),
+        });
+        
+        println!("Memory threshold: {:?}", gc.memory_threshold());
+        println!("Allocated memory: {} bytes", gc.allocated_memory());
+        println!("Object count: {}", gc.object_count());
+        
+        // 保持引用以防止被回收
+        let _keep_ref = obj1;
+    }
+
+    #[test]
+    fn test_from_iter() {
+        let objects: Vec<GCArc<TestObjectCell>> = (0..10)
+            .map(|_| GCArc::new(TestObjectCell(RefCell::new(TestObject { value: None }))))
+            .collect();
+
+        let mut gc: GC<TestObjectCell> = objects.iter().cloned().collect();
+        assert_eq!(gc.object_count(), 10);
+        assert_eq!(
+            gc.allocated_memory(),
+            10 * (std::mem::size_of::<TestObjectCell>()
+                + std::mem::size_of::<GCArc<TestObjectCell>>())
+        );
+
+        drop(objects);
+        gc.collect();
+        assert_eq!(gc.object_count(), 0);
+        assert_eq!(gc.allocated_memory(), 0);
+    }
+
+    #[derive(Clone)]
+    struct ValueObject(i32);
+
+    impl GCTraceable<ValueObject> for ValueObject {
+        fn collect(&self, _queue: &mut VecDeque<GCArcWeak<ValueObject>>) {}
+    }
+
+    impl std::fmt::Display for ValueObject {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "ValueObject({})", self.0)
+        }
+    }
+
+    #[test]
+    fn test_make_mut() {
+        let mut unique = GCArc::new(ValueObject(1));
+        assert!(unique.is_unique());
+        unique.make_mut().0 = 2;
+        assert_eq!(unique.as_ref().0, 2);
+
+        // 被GC追踪的对象不是唯一引用，make_mut 会复制出新的分配
+        let mut gc: GC<ValueObject> = GC::new();
+        let attached = gc.create(ValueObject(3));
+        let mut copy = attached.clone();
+        assert!(!copy.is_unique());
+        copy.make_mut().0 = 4;
+        assert!(copy.is_unique());
+        assert_eq!(attached.as_ref().0, 3);
+        assert_eq!(copy.as_ref().0, 4);
+        assert_eq!(gc.object_count(), 1);
+    }
+
+    #[test]
+    fn test_auto_collect_disabled() {
+        let mut gc: GC<ValueObject> = GC::new_with_percentage(1);
+        gc.set_auto_collect(false);
+        assert!(!gc.auto_collect());
+
+        // 没有外部引用的对象在关闭自动回收时不会被attach回收
+        for i in 0..10 {
+            let _ = gc.create(ValueObject(i));
+        }
+        assert_eq!(gc.object_count(), 10);
+
+        gc.collect();
+        assert_eq!(gc.object_count(), 0);
+    }
+
+    #[test]
+    fn test_upgrade_and_mark() {
+        let obj = GCArc::new(ValueObject(1));
+        let weak = obj.as_weak();
+        assert!(!obj.is_marked());
+
+        let upgraded = weak.upgrade_and_mark().unwrap();
+        assert!(obj.is_marked());
+        assert!(upgraded.is_marked());
+
+        obj.clear_mark();
+        assert!(!obj.is_marked());
+
+        drop(upgraded);
+        drop(obj);
+        assert!(weak.upgrade_and_mark().is_none());
+    }
+
+    #[test]
+    fn test_weak_tracking() {
+        let mut gc: GC<ValueObject> = GC::new_weak_tracking();
+        gc.set_auto_collect(false);
+        assert!(gc.is_weak_tracking());
+
+        let kept = gc.create(ValueObject(1));
+        let dropped = gc.create(ValueObject(2));
+        let weak = dropped.as_weak();
+        assert_eq!(kept.strong_ref(), 1);
+        assert_eq!(gc.object_count(), 2);
+
+        // GC不持有强引用，外部引用消失后对象立即被释放
+        drop(dropped);
+        assert!(!weak.is_valid());
+        assert_eq!(gc.get_all().len(), 1);
+
+        gc.collect();
+        assert_eq!(gc.object_count(), 1);
+
+        assert!(gc.detach(&kept));
+        assert_eq!(gc.object_count(), 0);
+        assert_eq!(gc.allocated_memory(), 0);
+    }
+
+    #[test]
+    fn test_plan_collection() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+
+        let kept = gc.create(ValueObject(1));
+        let garbage = gc.create(ValueObject(2)).as_weak();
+
+        let planned = gc.plan_collection();
+        assert_eq!(planned.len(), 1);
+        assert_eq!(planned[0].as_wrapper_ptr(), garbage.as_wrapper_ptr());
+        assert_eq!(gc.object_count(), 2);
+        assert_eq!(garbage.strong_ref(), 1);
+
+        drop(planned);
+        gc.collect();
+        assert_eq!(gc.object_count(), 1);
+        assert!(!garbage.is_valid());
+        assert_eq!(kept.as_ref().0, 1);
+    }
+
+    #[test]
+    fn test_pinned() {
+        let mut gc: GC<TestObjectCell> = GC::new();
+        gc.set_auto_collect(false);
+
+        let pinned = gc.create(TestObjectCell(RefCell::new(TestObject { value: None })));
+        let child = gc.create(TestObjectCell(RefCell::new(TestObject { value: None })));
+        pinned.as_ref().0.borrow_mut().value = Some(child.as_weak());
+        pinned.set_pinned(true);
+        assert!(pinned.is_pinned());
+
+        let pinned_weak = pinned.as_weak();
+        let child_weak = child.as_weak();
+        drop(pinned);
+        drop(child);
+
+        // 固定的对象及其子图不会被回收
+        gc.collect();
+        assert_eq!(gc.object_count(), 2);
+
+        pinned_weak.upgrade().unwrap().set_pinned(false);
+        gc.collect();
+        assert_eq!(gc.object_count(), 0);
+        assert!(!child_weak.is_valid());
+    }
+
+    #[test]
+    fn test_collect_into_pool() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+
+        let kept = gc.create(ValueObject(0));
+        for i in 1..4 {
+            let _ = gc.create(ValueObject(i));
+        }
+
+        let mut pool = Vec::new();
+        gc.collect_into_pool(&mut pool);
+        let mut values: Vec<i32> = pool.iter().map(|v| v.0).collect();
+        values.sort();
+        assert_eq!(values, vec![1, 2, 3]);
+        assert_eq!(gc.object_count(), 1);
+        assert_eq!(kept.as_ref().0, 0);
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        for i in 0..5 {
+            let _ = gc.create(ValueObject(i));
+        }
+
+        let mut drained = gc.drain();
+        let first = drained.next().unwrap();
+        assert_eq!(first.strong_ref(), 1);
+        drop(drained);
+
+        assert_eq!(gc.object_count(), 0);
+        assert_eq!(gc.allocated_memory(), 0);
+
+        // 被移出的对象不再被GC追踪，重新attach后可以正常回收
+        gc.attach(&first);
+        assert_eq!(gc.object_count(), 1);
+        drop(first);
+        gc.collect();
+        assert_eq!(gc.object_count(), 0);
+    }
+
+    #[test]
+    fn test_drain_weak_tracking() {
+        let obj_size =
+            std::mem::size_of::<ValueObject>() + std::mem::size_of::<GCArc<ValueObject>>();
+        let mut gc: GC<ValueObject> = GC::new_weak_tracking();
+        gc.set_auto_collect(false);
+        let kept: Vec<_> = (0..3).map(|i| gc.create(ValueObject(i))).collect();
+        let _ = gc.create(ValueObject(3));
+        assert_eq!(gc.allocated_memory(), 4 * obj_size);
+
+        // 内存估算随迭代逐个扣除，已被释放的对象不会被返回但同样被扣除
+        let mut drained = gc.drain();
+        assert!(drained.next().is_some());
+        assert_eq!(drained.gc.allocated_memory(), 3 * obj_size);
+        assert_eq!(drained.count(), 2);
+        assert_eq!(gc.allocated_memory(), 0);
+        assert_eq!(gc.object_count(), 0);
+        drop(kept);
+    }
+
+    #[test]
+    #[allow(clippy::mutable_key_type)] // ById 按地址哈希，内部可变性不影响哈希值
+    fn test_by_id() {
+        use crate::arc::ById;
+        use std::collections::HashSet;
+
+        let a = GCArc::new(ValueObject(1));
+        let b = GCArc::new(ValueObject(1));
+
+        let mut set = HashSet::new();
+        assert!(set.insert(ById(a.clone())));
+        assert!(!set.insert(ById(a.clone())));
+        assert!(set.insert(ById(b.clone())));
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_collection_stats_depth() {
+        let mut gc: GC<TestObjectCell> = GC::new();
+        gc.set_auto_collect(false);
+
+        // 构造一条长度为5的链：root -> n1 -> n2 -> n3 -> n4
+        let root = gc.create(TestObjectCell(RefCell::new(TestObject { value: None })));
+        let mut prev = root.clone();
+        for _ in 0..4 {
+            let next = gc.create(TestObjectCell(RefCell::new(TestObject { value: None })));
+            prev.as_ref().0.borrow_mut().value = Some(next.as_weak());
+            prev = next;
+        }
+        drop(prev);
+
+        gc.collect();
+        assert_eq!(gc.object_count(), 5);
+        let stats = gc.last_collection_stats();
+        assert_eq!(stats.max_depth, 5);
+        assert_eq!(stats.max_queue_len, 1);
+    }
+
+    #[test]
+    fn test_track() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+
+        let obj = GCArc::new(ValueObject(1));
+        assert!(!gc.contains(&obj));
+        assert!(gc.track(&obj));
+        assert!(!gc.track(&obj));
+        assert!(gc.contains(&obj));
+        assert_eq!(gc.object_count(), 1);
+        assert_eq!(obj.strong_ref(), 2);
+
+        // 已被其他GC追踪的对象仍需检查是否属于当前GC
+        let mut other: GC<ValueObject> = GC::new();
+        other.set_auto_collect(false);
+        assert!(other.track(&obj));
+        assert!(!other.track(&obj));
+        assert_eq!(obj.tracked_by_count(), 2);
+
+        let mut weak_gc: GC<ValueObject> = GC::new_weak_tracking();
+        assert!(weak_gc.track(&obj));
+        assert!(!weak_gc.track(&obj));
+    }
+
+    #[test]
+    fn test_freeze_subgraph() {
+        let mut gc: GC<TestObjectCell> = GC::new();
+        gc.set_auto_collect(false);
+
+        let root = gc.create(TestObjectCell(RefCell::new(TestObject { value: None })));
+        let child = gc.create(TestObjectCell(RefCell::new(TestObject { value: None })));
+        root.as_ref().0.borrow_mut().value = Some(child.as_weak());
+        drop(child);
+
+        gc.freeze_subgraph(&root);
+        gc.collect();
+        assert_eq!(gc.object_count(), 2);
+        // 根对象被标记后，整个子图直接被视为存活
+        assert_eq!(gc.last_collection_stats().max_depth, 1);
+
+        assert!(gc.unfreeze(&root));
+        assert!(!gc.unfreeze(&root));
+        gc.collect();
+        assert_eq!(gc.object_count(), 2);
+        assert_eq!(gc.last_collection_stats().max_depth, 2);
+
+        drop(root);
+        gc.collect();
+        assert_eq!(gc.object_count(), 0);
+    }
+
+    #[test]
+    fn test_cached_weak() {
+        use crate::arc::CachedWeak;
+
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let obj = gc.create(ValueObject(1));
+
+        let mut cached = CachedWeak::new(obj.as_weak());
+        assert_eq!(cached.get().unwrap().as_ref().0, 1);
+        assert_eq!(obj.strong_ref(), 2);
+
+        // 不持有强引用，不影响对象被回收
+        drop(obj);
+        gc.collect();
+        assert_eq!(gc.object_count(), 0);
+        assert!(cached.get().is_none());
+        assert!(!cached.is_valid());
+
+        let other = gc.create(ValueObject(2));
+        cached.set(other.as_weak());
+        assert_eq!(cached.get().unwrap().as_ref().0, 2);
+    }
+
+    #[test]
+    fn test_wrapper_layout() {
+        let obj = GCArc::new(ValueObject(42));
+        let value_ptr = obj.as_wrapper_ptr() as *const ValueObject;
+        assert_eq!(value_ptr, obj.as_ref() as *const ValueObject);
+        assert_eq!(obj.as_weak().as_wrapper_ptr(), obj.as_wrapper_ptr());
+    }
+
+    #[test]
+    fn test_collect_pipelined() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+
+        let kept: Vec<_> = (0..100).map(|i| gc.create(ValueObject(i))).collect();
+        let garbage: Vec<_> = (0..10_000)
+            .map(|i| gc.create(ValueObject(i)).as_weak())
+            .collect();
+
+        gc.collect_pipelined();
+        assert_eq!(gc.object_count(), kept.len());
+        assert!(garbage.iter().all(|w| !w.is_valid()));
+        assert_eq!(
+            gc.allocated_memory(),
+            kept.len()
+                * (std::mem::size_of::<ValueObject>() + std::mem::size_of::<GCArc<ValueObject>>())
+        );
+    }
+
+    #[test]
+    fn test_object_id() {
+        let a = GCArc::new(ValueObject(1));
+        let b = GCArc::new(ValueObject(1));
+        assert_ne!(a.id(), b.id());
+        assert_eq!(a.clone().id(), a.id());
+        assert!(GCArc::<ValueObject>::creation_count() > a.id().max(b.id()));
+    }
+
+    #[test]
+    fn test_drop_panic_during_sweep() {
+        struct PanicOnDrop(bool);
+
+        impl GCTraceable<PanicOnDrop> for PanicOnDrop {
+            fn collect(&self, _queue: &mut VecDeque<GCArcWeak<PanicOnDrop>>) {}
+        }
+
+        impl Drop for PanicOnDrop {
+            fn drop(&mut self) {
+                if self.0 {
+                    panic!("PanicOnDrop");
+                }
+            }
+        }
+
+        let mut gc: GC<PanicOnDrop> = GC::new();
+        gc.set_auto_collect(false);
+        let kept = gc.create(PanicOnDrop(false));
+        let others: Vec<_> = [true, false, true]
+            .into_iter()
+            .map(|p| gc.create(PanicOnDrop(p)).as_weak())
+            .collect();
+
+        gc.collect();
+        assert!(others.iter().all(|w| !w.is_valid()));
+        assert_eq!(gc.object_count(), 1);
+        assert_eq!(gc.take_drop_panics().len(), 2);
+        assert!(gc.take_drop_panics().is_empty());
+
+        // 回收器在捕获panic后仍可正常使用
+        gc.attach(&kept);
+        assert_eq!(gc.object_count(), 2);
+    }
+
+    #[test]
+    fn test_break_cycles() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static CLEARED: AtomicUsize = AtomicUsize::new(0);
+
+        struct Link(Option<GCArc<Link>>);
+
+        impl GCTraceable<Link> for Link {
+            fn collect(&self, queue: &mut VecDeque<GCArcWeak<Link>>) {
+                if let Some(next) = &self.0 {
+                    queue.push_back(next.as_weak());
+                }
+            }
+
+            fn clear_edges(&mut self) {
+                CLEARED.fetch_add(1, Ordering::Relaxed);
+                self.0 = None;
+            }
+        }
+
+        let mut gc: GC<Link> = GC::new();
+        gc.set_auto_collect(false);
+        let kept = gc.create(Link(None));
+
+        let mut head = None;
+        for _ in 0..3 {
+            let link = GCArc::new(Link(head.take()));
+            gc.attach(&link);
+            head = Some(link);
+        }
+        drop(head);
+
+        // 链中对象持有下一个对象的强引用，每次只有链头不可达；
+        // 清空链头的引用后，下一个对象在下一次回收中变为不可达
+        gc.break_cycles();
+        assert_eq!(gc.object_count(), 3);
+        gc.break_cycles();
+        gc.break_cycles();
+        assert_eq!(gc.object_count(), 1);
+        assert!(gc.contains(&kept));
+        assert_eq!(CLEARED.load(Ordering::Relaxed), 3);
+    }
+
+    #[test]
+    fn test_reachable() {
+        let a = GCArc::new(TestObjectCell(RefCell::new(TestObject { value: None })));
+        let b = GCArc::new(TestObjectCell(RefCell::new(TestObject { value: None })));
+        let c = GCArc::new(TestObjectCell(RefCell::new(TestObject { value: None })));
+        a.as_ref().0.borrow_mut().value = Some(b.as_weak());
+        b.as_ref().0.borrow_mut().value = Some(c.as_weak());
+
+        assert!(reachable(&a, &c.as_weak(), usize::MAX));
+        assert!(!reachable(&c, &a.as_weak(), usize::MAX));
+        // 访问上限不足以到达目标
+        assert!(!reachable(&a, &c.as_weak(), 1));
+    }
+
+    #[test]
+    fn test_grace_period() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        gc.set_grace_period(2);
+
+        let young = gc.create(ValueObject(1)).as_weak();
+        gc.collect();
+        assert!(young.is_valid());
+        gc.collect();
+        assert!(young.is_valid());
+
+        // 宽限期结束后对象可以被正常回收
+        gc.collect();
+        assert!(!young.is_valid());
+        assert_eq!(gc.object_count(), 0);
+    }
+
+    #[test]
+    fn test_grace_period_keyed_by_id() {
+        struct NoRoots;
+        impl RootScanner<ValueObject> for NoRoots {
+            fn scan_roots(
+                &self,
+                _: &GC<ValueObject>,
+                _: &[GCArc<ValueObject>],
+                _: &mut VecDeque<GCArcWeak<ValueObject>>,
+            ) {
+            }
+        }
+
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        gc.set_grace_period(3);
+
+        // 扫描器不考虑宽限期，处于宽限期的对象也会被回收，留下的条目以id为键，
+        // 即使新对象复用了它的地址也不会继承剩余的宽限期
+        let first = gc.create(ValueObject(1)).as_weak();
+        gc.collect_with_scanner(&NoRoots);
+        assert!(!first.is_valid());
+        gc.set_grace_period(0);
+        let second = gc.create(ValueObject(2)).as_weak();
+        assert!(!gc.young.lock().unwrap().contains_key(&second.upgrade().unwrap().id()));
+        gc.collect();
+        assert!(!second.is_valid());
+    }
+
+    #[test]
+    fn test_with_upgraded() {
+        let obj = GCArc::new(ValueObject(7));
+        let weak = obj.as_weak();
+        assert_eq!(weak.with_upgraded(|v| v.0 * 2), Some(14));
+        assert_eq!(obj.strong_ref(), 1);
+
+        drop(obj);
+        assert_eq!(weak.with_upgraded(|v| v.0), None);
+    }
+
+    #[test]
+    fn test_total_allocated_bytes() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let obj_size =
+            std::mem::size_of::<ValueObject>() + std::mem::size_of::<GCArc<ValueObject>>();
+
+        for i in 0..4 {
+            let _ = gc.create(ValueObject(i));
+        }
+        gc.collect();
+        assert_eq!(gc.allocated_memory(), 0);
+        assert_eq!(gc.total_allocated_bytes(), 4 * obj_size);
+    }
+
+    #[test]
+    fn test_display() {
+        let obj = GCArc::new(ValueObject(3));
+        assert_eq!(format!("{}", obj), "ValueObject(3)");
+    }
+
+    #[test]
+    fn test_on_post_sweep() {
+        use std::sync::Arc;
+
+        let swept = Arc::new(Mutex::new(Vec::new()));
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let sink = swept.clone();
+        gc.on_post_sweep(move |ids| sink.lock().unwrap().extend_from_slice(ids));
+
+        let kept = gc.create(ValueObject(0));
+        let garbage_id = gc.create(ValueObject(1)).id();
+        gc.collect();
+        assert_eq!(*swept.lock().unwrap(), vec![garbage_id]);
+        assert!(gc.contains(&kept));
+    }
+
+    #[test]
+    fn test_hard_limit() {
+        let obj_size =
+            std::mem::size_of::<ValueObject>() + std::mem::size_of::<GCArc<ValueObject>>();
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        gc.set_hard_limit(Some(2 * obj_size));
+
+        let a = gc.try_create(ValueObject(1)).unwrap();
+        let b = gc.try_create(ValueObject(2)).unwrap();
+        assert_eq!(
+            gc.try_create(ValueObject(3)).err(),
+            Some(GCError::OutOfMemory {
+                requested: 3 * obj_size,
+                limit: 2 * obj_size,
+            })
+        );
+        assert_eq!(gc.object_count(), 2);
+
+        // 强制回收释放空间后可以继续添加
+        drop(b);
+        assert!(gc.try_create(ValueObject(3)).is_ok());
+        assert!(gc.contains(&a));
+    }
+
+    #[test]
+    fn test_gc_cow() {
+        use crate::arc::GCCow;
+
+        let obj = GCArc::new(ValueObject(5));
+        let borrowed = GCCow::from(&obj);
+        assert!(borrowed.is_borrowed());
+        assert_eq!(borrowed.0, 5);
+        assert_eq!(obj.strong_ref(), 1);
+
+        let owned = borrowed.into_owned();
+        assert_eq!(obj.strong_ref(), 2);
+
+        let cow = GCCow::from(owned);
+        assert!(!cow.is_borrowed());
+        let owned = cow.into_owned();
+        assert_eq!(owned.strong_ref(), 2);
+    }
+
+    #[test]
+    fn test_collection_progress() {
+        use crate::gc::CollectionProgress;
+        use std::sync::{Arc, OnceLock};
+
+        struct Probe {
+            progress: OnceLock<Arc<CollectionProgress>>,
+            seen: Mutex<Option<(usize, usize)>>,
+        }
+        impl GCTraceable<Probe> for Probe {
+            fn collect(&self, _queue: &mut VecDeque<GCArcWeak<Probe>>) {
+                *self.seen.lock().unwrap() = self.progress.get().and_then(|p| p.get());
+            }
+        }
+
+        let mut gc: GC<Probe> = GC::new();
+        gc.set_auto_collect(false);
+        let probe = gc.create(Probe {
+            progress: OnceLock::new(),
+            seen: Mutex::new(None),
+        });
+        let _ = probe.as_ref().progress.set(gc.progress_handle());
+        let _ = gc.create(Probe {
+            progress: OnceLock::new(),
+            seen: Mutex::new(None),
+        });
+        assert_eq!(gc.collection_progress(), None);
+
+        gc.collect();
+        // 访问根对象时它已被标记，清除阶段尚未开始
+        assert_eq!(*probe.as_ref().seen.lock().unwrap(), Some((1, 0)));
+        assert_eq!(gc.collection_progress(), None);
+        assert_eq!(gc.object_count(), 1);
+    }
+
+    #[test]
+    fn test_strong_edges() {
+        use crate::traceable::Edge;
+        use std::sync::atomic::AtomicUsize;
+
+        static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+        struct StrongNode {
+            children: RefCell<Vec<GCArc<StrongNode>>>,
+        }
+        impl Drop for StrongNode {
+            fn drop(&mut self) {
+                DROPPED.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            }
+        }
+        impl GCTraceable<StrongNode> for StrongNode {
+            const TRACES_STRONG_EDGES: bool = true;
+
+            fn collect(&self, queue: &mut VecDeque<GCArcWeak<StrongNode>>) {
+                queue.extend(self.children.borrow().iter().map(|c| c.as_weak()));
+            }
+
+            fn edges(&self, edges: &mut Vec<Edge<StrongNode>>) {
+                edges.extend(self.children.borrow().iter().cloned().map(Edge::Strong));
+            }
+
+            fn release_strong_edges(&self) {
+                self.children.borrow_mut().clear();
+            }
+        }
+
+        let mut gc: GC<StrongNode> = GC::new();
+        gc.set_auto_collect(false);
+        let new_node = || StrongNode {
+            children: RefCell::new(Vec::new()),
+        };
+        let a = gc.create(new_node());
+        let b = gc.create(new_node());
+        a.as_ref().children.borrow_mut().push(b.clone());
+        b.as_ref().children.borrow_mut().push(a.clone());
+
+        // 存在外部引用时整个环都存活
+        gc.collect();
+        assert_eq!(gc.object_count(), 2);
+
+        // 环内的强引用边不会使对象被视为根对象
+        drop(b);
+        assert!(gc.plan_collection().is_empty());
+        let weak = a.as_weak();
+        drop(a);
+        assert_eq!(gc.plan_collection().len(), 2);
+        gc.collect();
+        assert_eq!(gc.object_count(), 0);
+
+        // 清除时强引用边被丢弃，环中的对象真正被释放
+        assert!(!weak.is_valid());
+        assert_eq!(DROPPED.load(std::sync::atomic::Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_num_roots() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let _a = gc.create(ValueObject(1));
+        let b = gc.create(ValueObject(2));
+        let _ = gc.create(ValueObject(3));
+        assert_eq!(gc.num_roots(), 2);
+
+        drop(b);
+        assert_eq!(gc.num_roots(), 1);
+
+        // 被固定的对象即使没有外部引用也是根对象
+        gc.create(ValueObject(4)).set_pinned(true);
+        assert_eq!(gc.num_roots(), 2);
+        assert_eq!(gc.object_count(), 4);
+    }
+
+    #[test]
+    fn test_gc_slot() {
+        use crate::arc::GCSlot;
+
+        let first = GCArc::new(ValueObject(1));
+        let slot = GCSlot::new(first.clone());
+        assert_eq!(first.strong_ref(), 2);
+        assert_eq!(slot.load().as_ref().0, 1);
+
+        let old = slot.swap(GCArc::new(ValueObject(2)));
+        assert!(GCArc::ptr_eq(&old, &first));
+        drop(old);
+        assert_eq!(first.strong_ref(), 1);
+
+        std::thread::scope(|s| {
+            for i in 0..4 {
+                let slot = &slot;
+                s.spawn(move || {
+                    for j in 0..1000 {
+                        if j % 2 == 0 {
+                            slot.store(GCArc::new(ValueObject(i)));
+                        } else {
+                            assert!(slot.load().as_ref().0 < 4);
+                        }
+                    }
+                });
+            }
+        });
+        let last = slot.load();
+        assert_eq!(last.strong_ref(), 2);
+        drop(slot);
+        assert_eq!(last.strong_ref(), 1);
+    }
+
+    #[test]
+    fn test_gc_slot_swap_under_constant_reads() {
+        use crate::arc::GCSlot;
+        use std::sync::atomic::AtomicBool;
+
+        // 读者持续读取时读者计数几乎不会归零，`swap` 仍然必须能够完成
+        let slot = GCSlot::new(GCArc::new(ValueObject(0)));
+        let done = AtomicBool::new(false);
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                let (slot, done) = (&slot, &done);
+                s.spawn(move || {
+                    while !done.load(std::sync::atomic::Ordering::Relaxed) {
+                        assert!(slot.load().as_ref().0 <= 100);
+                    }
+                });
+            }
+            for i in 1..=100 {
+                slot.store(GCArc::new(ValueObject(i)));
+            }
+            done.store(true, std::sync::atomic::Ordering::Relaxed);
+        });
+        assert_eq!(slot.load().as_ref().0, 100);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let a = gc.create(ValueObject(1));
+        let _ = gc.create(ValueObject(2));
+        assert_eq!(gc.validate(), Ok(()));
+
+        gc.collect();
+        assert_eq!(gc.validate(), Ok(()));
+
+        let _ = a.as_weak().upgrade_and_mark();
+        assert_eq!(
+            gc.validate(),
+            Err(vec![format!("object {} is still marked", a.id())])
+        );
+        a.clear_mark();
+        assert_eq!(gc.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_detach_reattach() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let obj = gc.create(ValueObject(1));
+        let attached = |obj: &GCArc<ValueObject>| {
+            obj.inner()
+                .attached_gc_count
+                .load(std::sync::atomic::Ordering::Relaxed)
+        };
+
+        assert!(gc.detach(&obj));
+        assert_eq!(attached(&obj), 0);
+        assert_eq!(obj.strong_ref(), 1);
+
+        // 重新添加后计数恢复，外部引用仍使其作为根对象存活
+        gc.attach(&obj);
+        assert_eq!(attached(&obj), 1);
+        assert_eq!(obj.strong_ref(), 2);
+        gc.collect();
+        assert!(gc.contains(&obj));
+        assert_eq!(gc.validate(), Ok(()));
+
+        // 重新添加的对象在失去外部引用后可以被正常回收
+        let weak = obj.as_weak();
+        drop(obj);
+        gc.collect();
+        assert!(!weak.is_valid());
+
+        // 没有外部引用的被移出对象会被立即释放，之后无法再被添加
+        let obj = gc.create(ValueObject(2));
+        let weak = obj.as_weak();
+        gc.detach(&obj);
+        drop(obj);
+        assert!(weak.upgrade().is_none());
+        assert_eq!(gc.object_count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "access-tracking")]
+    fn test_collect_lru() {
+        struct Entry {
+            children: Vec<GCArcWeak<Entry>>,
+        }
+        impl GCTraceable<Entry> for Entry {
+            fn collect(&self, queue: &mut VecDeque<GCArcWeak<Entry>>) {
+                queue.extend(self.children.iter().cloned());
+            }
+        }
+
+        let obj_size = std::mem::size_of::<Entry>() + std::mem::size_of::<GCArc<Entry>>();
+        let mut gc: GC<Entry> = GC::new_with_memory_threshold(2 * obj_size);
+        gc.set_auto_collect(false);
+
+        // 4个只通过索引对象的弱引用可达的条目
+        let entries: Vec<_> = (0..4)
+            .map(|_| gc.create(Entry { children: Vec::new() }))
+            .collect();
+        let weaks: Vec<_> = entries.iter().map(|e| e.as_weak()).collect();
+        let _index = gc.create(Entry {
+            children: weaks.clone(),
+        });
+        drop(entries);
+        let _ = weaks[2].upgrade();
+
+        // 5个对象保留一半（2个）：索引对象是根对象，再保留最近被访问的条目
+        assert_eq!(gc.collect_lru(0.5), 3);
+        assert_eq!(gc.object_count(), 2);
+        assert!(weaks[2].is_valid());
+        assert!(!weaks[0].is_valid() && !weaks[1].is_valid() && !weaks[3].is_valid());
+        assert_eq!(gc.validate(), Ok(()));
+
+        // 低于内存阈值时不再淘汰
+        assert_eq!(gc.collect_lru(0.5), 0);
+    }
+
+    #[test]
+    fn test_object_count_by() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let _objs: Vec<_> = (0..5).map(|i| gc.create(ValueObject(i))).collect();
+
+        let counts = gc.object_count_by(|v| v.0 % 2 == 0);
+        assert_eq!(counts.get(&true), Some(&3));
+        assert_eq!(counts.get(&false), Some(&2));
+    }
+
+    #[test]
+    fn test_current_gc() {
+        let gc = Arc::new(Mutex::new(GC::<ValueObject>::new()));
+        gc.lock().unwrap().set_auto_collect(false);
+
+        let obj = GC::with_current(gc.clone(), || GCArc::new_tracked(ValueObject(1)));
+        assert!(gc.lock().unwrap().contains(&obj));
+        assert!(GC::<ValueObject>::current().is_none());
+
+        let other = Arc::new(Mutex::new(GC::<ValueObject>::new()));
+        assert!(GC::set_current(other.clone()).is_none());
+        let nested = GC::with_current(gc.clone(), || GCArc::new_tracked(ValueObject(2)));
+        assert!(gc.lock().unwrap().contains(&nested));
+        assert!(Arc::ptr_eq(&GC::<ValueObject>::current().unwrap(), &other));
+        assert!(GC::<ValueObject>::clear_current().is_some());
+    }
+
+    #[test]
+    fn test_object_in_two_gcs() {
+        let mut gc_a: GC<ValueObject> = GC::new();
+        let mut gc_b: GC<ValueObject> = GC::new();
+        gc_a.set_auto_collect(false);
+        gc_b.set_auto_collect(false);
+        let attached = |obj: &GCArc<ValueObject>| {
+            obj.inner()
+                .attached_gc_count
+                .load(std::sync::atomic::Ordering::Relaxed)
+        };
+
+        let obj = gc_a.create(ValueObject(1));
+        gc_b.attach(&obj);
+        assert_eq!(attached(&obj), 2);
+
+        // 第一个GC回收后计数只减少1，对象仍被第二个GC正确追踪
+        let weak = obj.as_weak();
+        drop(obj);
+        gc_a.collect();
+        assert_eq!(gc_a.object_count(), 0);
+        let obj = weak.upgrade().unwrap();
+        assert_eq!(attached(&obj), 1);
+        assert_eq!(gc_b.validate(), Ok(()));
+
+        drop(obj);
+        gc_b.collect();
+        assert!(!weak.is_valid());
+    }
+
+    #[test]
+    fn test_same_object_as() {
+        let a = GCArc::new(ValueObject(1));
+        let b = GCArc::new(ValueObject(1));
+        assert!(a.same_object_as(&a.as_weak()));
+        assert!(!a.same_object_as(&b.as_weak()));
+
+        let dead = GCArc::new(ValueObject(2)).as_weak();
+        let c = GCArc::new(ValueObject(2));
+        assert!(!dead.is_valid());
+        assert!(!c.same_object_as(&dead));
+    }
+
+    #[test]
+    fn test_size_histogram() {
+        struct Blob(Vec<u8>);
+        impl GCTraceable<Blob> for Blob {
+            fn collect(&self, _queue: &mut VecDeque<GCArcWeak<Blob>>) {}
+
+            fn size_hint(&self) -> usize {
+                std::mem::size_of::<Self>() + self.0.len()
+            }
+        }
+
+        let mut gc: GC<Blob> = GC::new();
+        gc.set_auto_collect(false);
+        let base = std::mem::size_of::<Blob>();
+        let _small: Vec<_> = (0..3).map(|_| gc.create(Blob(Vec::new()))).collect();
+        let _large = gc.create(Blob(vec![0; 1000]));
+
+        let histogram = gc.size_histogram();
+        assert_eq!(histogram.get(&base.next_power_of_two()), Some(&3));
+        assert_eq!(histogram.get(&(base + 1000).next_power_of_two()), Some(&1));
+        assert_eq!(histogram.values().sum::<usize>(), 4);
+    }
+
+    #[test]
+    fn test_reserve_and_growth_increment() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        gc.reserve(10);
+        assert!(gc.spare_capacity() >= 10);
+
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        gc.set_growth_increment(Some(4));
+        let objs: Vec<_> = (0..5).map(|i| gc.create(ValueObject(i))).collect();
+        // 第5个对象触发第二次扩容，容量为8
+        assert_eq!(gc.spare_capacity(), 3);
+        assert_eq!(objs.len(), gc.object_count());
+    }
+
+    #[test]
+    fn test_upgrade_if_tracked() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let obj = gc.create(ValueObject(1));
+        let weak = obj.as_weak();
+        assert!(weak.upgrade_if_tracked().is_some());
+
+        gc.detach(&obj);
+        assert!(weak.upgrade_if_tracked().is_none());
+        assert!(weak.upgrade().is_some());
+    }
+
+    #[test]
+    fn test_upgrade_if_tracked_weak_tracking() {
+        // 弱引用追踪模式不计入 `attached_gc_count`，被追踪的对象同样返回 `None`
+        let mut gc: GC<ValueObject> = GC::new_weak_tracking();
+        gc.set_auto_collect(false);
+        let obj = gc.create(ValueObject(1));
+        let weak = obj.as_weak();
+        assert!(gc.contains(&obj));
+        assert!(weak.upgrade_if_tracked().is_none());
+        assert!(weak.upgrade().is_some());
+    }
+
+    #[test]
+    fn test_heap_snapshot_diff() {
+        use crate::gc::RefcountDelta;
+
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let a = gc.create(ValueObject(1));
+        let b = gc.create(ValueObject(2));
+        let before = gc.snapshot();
+        assert_eq!(before.counts.get(&a.id()), Some(&(2, 0)));
+
+        let leaked = vec![a.clone(), a.clone()];
+        let _weak = b.as_weak();
+        let after = gc.snapshot();
+        assert_eq!(
+            before.diff(&after),
+            vec![
+                RefcountDelta {
+                    id: a.id(),
+                    strong_delta: 2,
+                    weak_delta: 0,
+                },
+                RefcountDelta {
+                    id: b.id(),
+                    strong_delta: 0,
+                    weak_delta: 1,
+                },
+            ]
+        );
+        drop(leaked);
+        assert_eq!(before.diff(&gc.snapshot()).len(), 1);
+    }
+
+    #[test]
+    fn test_collect_empty_heap() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let obj = gc.create(ValueObject(1));
+        gc.detach(&obj);
+        assert_eq!(gc.attach_count.load(std::sync::atomic::Ordering::Relaxed), 1);
+
+        gc.collect();
+        assert_eq!(gc.attach_count.load(std::sync::atomic::Ordering::Relaxed), 0);
+        assert_eq!(gc.last_collection_stats(), CollectionStats::default());
+        assert_eq!(gc.collection_progress(), None);
+    }
+
+    #[test]
+    fn test_pause_resume() {
+        let mut gc: GC<ValueObject> = GC::new_with_percentage(10);
+        let keep = gc.create(ValueObject(0));
+
+        gc.pause();
+        let weaks: Vec<_> = (0..20).map(|i| gc.create(ValueObject(i)).as_weak()).collect();
+        // 暂停期间新对象即使成为垃圾也不会被自动回收
+        assert!(weaks.iter().all(|w| w.is_valid()));
+        assert_eq!(gc.object_count(), 21);
+
+        // 恢复时执行被推迟的回收
+        gc.resume(true);
+        assert!(!gc.is_paused());
+        assert!(weaks.iter().all(|w| !w.is_valid()));
+        assert!(gc.contains(&keep));
+    }
+
+    #[test]
+    fn test_arc_conversion() {
+        let arc = Arc::new(ValueObject(3));
+        let shared = arc.clone();
+        let arc = GCArc::from_arc(arc).err().unwrap();
+        drop(shared);
+        let gc_arc = GCArc::from_arc(arc).ok().unwrap();
+        assert_eq!(gc_arc.as_ref().0, 3);
+
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        gc.attach(&gc_arc);
+        let gc_arc = gc_arc.into_arc().err().unwrap();
+        gc.detach(&gc_arc);
+        let arc = gc_arc.into_arc().ok().unwrap();
+        assert_eq!(arc.0, 3);
+    }
+
+    #[test]
+    fn test_collect_explained() {
+        let mut gc: GC<TestObjectCell> = GC::new();
+        gc.set_auto_collect(false);
+        let root = gc.create(TestObjectCell(RefCell::new(TestObject { value: None })));
+        let child = gc.create(TestObjectCell(RefCell::new(TestObject { value: None })));
+        root.as_ref().0.borrow_mut().value = Some(child.as_weak());
+        let (root_id, child_id) = (root.id(), child.id());
+        drop(child);
+
+        let pinned = gc.create(TestObjectCell(RefCell::new(TestObject { value: None })));
+        pinned.set_pinned(true);
+        let pinned_id = pinned.id();
+        drop(pinned);
+        let garbage_id = gc
+            .create(TestObjectCell(RefCell::new(TestObject { value: None })))
+            .id();
+
+        let mut fates = gc.collect_explained();
+        fates.sort_by_key(|(id, _)| *id);
+        assert_eq!(
+            fates,
+            vec![
+                (root_id, ObjectFate::KeptRoot),
+                (child_id, ObjectFate::KeptReachable),
+                (pinned_id, ObjectFate::KeptPinned),
+                (garbage_id, ObjectFate::Freed),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dedup_by() {
+        struct Value {
+            value: i32,
+            next: RefCell<Option<GCArcWeak<Value>>>,
+        }
+        impl GCTraceable<Value> for Value {
+            fn collect(&self, queue: &mut VecDeque<GCArcWeak<Value>>) {
+                queue.extend(self.next.borrow().iter().cloned());
+            }
+
+            fn rewrite_edges(&self, remap: &dyn Fn(&GCArcWeak<Value>) -> Option<GCArcWeak<Value>>) {
+                let mut next = self.next.borrow_mut();
+                if let Some(target) = next.as_ref().and_then(remap) {
+                    *next = Some(target);
+                }
+            }
+        }
+
+        let mut gc: GC<Value> = GC::new();
+        gc.set_auto_collect(false);
+        let new_value = |value| Value {
+            value,
+            next: RefCell::new(None),
+        };
+        let a = gc.create(new_value(1));
+        let b = gc.create(new_value(1));
+        let head = gc.create(new_value(0));
+        *head.as_ref().next.borrow_mut() = Some(b.as_weak());
+        let weak_b = b.as_weak();
+        drop(b);
+
+        assert_eq!(gc.dedup_by(|v| v.value), 1);
+        assert_eq!(gc.object_count(), 2);
+        assert!(!weak_b.is_valid());
+        let next = head.as_ref().next.borrow().clone().unwrap();
+        assert!(a.same_object_as(&next));
+        assert_eq!(gc.validate(), Ok(()));
+
+        // 被固定的对象不会被合并；被合并的对象与回收一样产生事件
+        let (sender, receiver) = std::sync::mpsc::sync_channel(16);
+        gc.set_event_sink(Some(sender));
+        let pinned = gc.create(new_value(1));
+        pinned.set_pinned(true);
+        let merged = gc.create(new_value(1)).id();
+        assert_eq!(gc.dedup_by(|v| v.value), 1);
+        assert!(gc.contains(&pinned));
+        let events: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0], CollectionEvent::CollectionStarted);
+        assert!(matches!(events[1], CollectionEvent::ObjectFreed { id, .. } if id == merged));
+        assert!(matches!(events[2], CollectionEvent::CollectionFinished(_)));
+    }
+
+    #[test]
+    fn test_soft_ref() {
+        let obj_size =
+            std::mem::size_of::<ValueObject>() + std::mem::size_of::<GCArc<ValueObject>>();
+        let mut gc: GC<ValueObject> = GC::new_with_memory_threshold(2 * obj_size);
+        gc.set_auto_collect(false);
+
+        // 没有内存压力时软引用保持目标存活
+        let obj = gc.create(ValueObject(1));
+        let soft = gc.soft_ref(&obj);
+        drop(obj);
+        gc.collect();
+        assert!(soft.is_valid());
+
+        // 丢弃软引用后目标不再被保持
+        drop(soft);
+        gc.collect();
+        assert_eq!(gc.object_count(), 0);
+
+        // 内存压力下软引用的目标会被回收
+        let obj = gc.create(ValueObject(1));
+        let soft = gc.soft_ref(&obj);
+        drop(obj);
+        let _others: Vec<_> = (0..2).map(|i| gc.create(ValueObject(i))).collect();
+        gc.collect();
+        assert!(soft.upgrade().is_none());
+        assert_eq!(gc.object_count(), 2);
+    }
+
+    /// 析构时升级 `target` 并保存到 `sink` 中，从而复活目标；`held` 是不由 `release_strong_edges` 释放的强引用边
+    struct Phoenix {
+        target: Option<GCArcWeak<Phoenix>>,
+        held: Option<GCArc<Phoenix>>,
+        sink: Arc<Mutex<Vec<GCArc<Phoenix>>>>,
+    }
+
+    impl Phoenix {
+        fn new(target: Option<GCArcWeak<Phoenix>>, sink: &Arc<Mutex<Vec<GCArc<Phoenix>>>>) -> Self {
+            Phoenix {
+                target,
+                held: None,
+                sink: sink.clone(),
+            }
+        }
+    }
+
+    impl GCTraceable<Phoenix> for Phoenix {
+        const TRACES_STRONG_EDGES: bool = true;
+
+        fn collect(&self, _queue: &mut VecDeque<GCArcWeak<Phoenix>>) {}
+
+        fn edges(&self, edges: &mut Vec<Edge<Phoenix>>) {
+            edges.extend(self.held.iter().cloned().map(Edge::Strong));
+        }
+    }
+
+    impl Drop for Phoenix {
+        fn drop(&mut self) {
+            if let Some(target) = self.target.as_ref().and_then(|t| t.upgrade()) {
+                self.sink.lock().unwrap().push(target);
+            }
+        }
+    }
+
+    #[test]
+    fn test_resurrection() {
+        let sink = Arc::new(Mutex::new(Vec::new()));
+        let mut gc: GC<Phoenix> = GC::new();
+        gc.set_auto_collect(false);
+        // `first` 先于 `second` 被追踪，因此先被释放，并在析构中复活 `second`
+        let second = GCArc::new(Phoenix::new(None, &sink));
+        let second_id = second.id();
+        let first = gc.create(Phoenix::new(Some(second.as_weak()), &sink));
+        gc.attach(&second);
+        drop((first, second));
+
+        let fates = gc.collect_explained();
+        assert!(fates.contains(&(second_id, ObjectFate::KeptRoot)));
+        assert_eq!(gc.object_count(), 1);
+        assert_eq!(sink.lock().unwrap()[0].id(), second_id);
+        assert_eq!(gc.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_resurrection_pipelined() {
+        let sink = Arc::new(Mutex::new(Vec::new()));
+        let mut gc: GC<Phoenix> = GC::new();
+        gc.set_auto_collect(false);
+        let second = GCArc::new(Phoenix::new(None, &sink));
+        let first = gc.create(Phoenix::new(Some(second.as_weak()), &sink));
+        gc.attach(&second);
+        drop((first, second));
+
+        gc.collect_pipelined();
+        // 被复活的对象重新被追踪，而不是脱离GC继续存活
+        assert_eq!(gc.object_count(), 1);
+        assert_eq!(gc.validate(), Ok(()));
+        drop(gc);
+        sink.lock().unwrap().clear();
+    }
+
+    #[test]
+    fn test_is_any_of() {
+        let a = GCArc::new(ValueObject(1));
+        let b = GCArc::new(ValueObject(1));
+        let seen = vec![b.clone(), GCArc::new(ValueObject(2))];
+        assert!(b.is_any_of(&seen));
+        assert!(!a.is_any_of(&seen));
+        assert!(!a.is_any_of(&[]));
+    }
+
+    #[test]
+    fn test_export_import_state() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let objs: Vec<_> = (0..3).map(|i| gc.create(ValueObject(i))).collect();
+        let state = gc.export_state();
+        assert_eq!(state.attach_count, 3);
+
+        let mut restored: GC<ValueObject> = GC::new();
+        restored.set_auto_collect(false);
+        restored.attach_many(objs.iter().cloned());
+        restored.import_state(state);
+        assert_eq!(restored.export_state(), state);
+        assert_eq!(restored.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_interior_mut_lock() {
+        use crate::arc::InteriorMut;
+
+        struct Counter(Mutex<i32>);
+        impl GCTraceable<Counter> for Counter {
+            fn collect(&self, _queue: &mut VecDeque<GCArcWeak<Counter>>) {}
+        }
+        impl InteriorMut for Counter {
+            type Target = i32;
+            type Guard<'a> = std::sync::MutexGuard<'a, i32>;
+
+            fn lock(&self) -> Self::Guard<'_> {
+                InteriorMut::lock(&self.0)
+            }
+        }
+
+        let mut gc: GC<Counter> = GC::new();
+        gc.set_auto_collect(false);
+        let counter = gc.create(Counter(Mutex::new(0)));
+        *counter.lock() += 5;
+        assert_eq!(*counter.as_ref().0.lock().unwrap(), 5);
+    }
+
+    #[test]
+    fn test_collection_intervals() {
+        let mut gc: GC<TestObjectCell> = GC::new_with_percentage(10);
+        gc.set_min_interval(Some(Duration::from_secs(3600)));
+        let _ = gc.create(TestObjectCell(RefCell::new(TestObject { value: None })));
+        let _ = gc.create(TestObjectCell(RefCell::new(TestObject { value: None })));
+        // 最短间隔内即使达到百分比阈值也不自动回收
+        assert_eq!(gc.object_count(), 2);
+
+        let mut gc: GC<TestObjectCell> = GC::new_with_percentage(1000);
+        let _ = gc.create(TestObjectCell(RefCell::new(TestObject { value: None })));
+        assert_eq!(gc.object_count(), 1);
+        gc.set_max_interval(Some(Duration::ZERO));
+        let _ = gc.create(TestObjectCell(RefCell::new(TestObject { value: None })));
+        // 超过最长间隔，attach时触发回收，只有刚创建、仍被外部持有的对象存活
+        assert_eq!(gc.object_count(), 1);
+    }
+
+    #[test]
+    fn test_clone_if() {
+        let mut gc: GC<TestObjectCell> = GC::new();
+        gc.set_auto_collect(false);
+        let obj = gc.create(TestObjectCell(RefCell::new(TestObject { value: None })));
+        assert!(obj.clone_if(false).is_none());
+        assert_eq!(obj.strong_ref(), 2);
+
+        let kept = obj.clone_if(true).unwrap();
+        assert!(GCArc::ptr_eq(&obj, &kept));
+        assert_eq!(obj.strong_ref(), 3);
+    }
+
+    #[test]
+    fn test_large_object_space() {
+        struct Blob(Vec<u8>);
+        impl GCTraceable<Blob> for Blob {
+            fn collect(&self, _queue: &mut VecDeque<GCArcWeak<Blob>>) {}
+
+            fn size_hint(&self) -> usize {
+                self.0.len()
+            }
+        }
+
+        let mut gc: GC<Blob> = GC::new();
+        gc.set_auto_collect(false);
+        gc.set_large_object_threshold(Some(1024));
+        let large = gc.create(Blob(vec![0; 4096])).as_weak();
+        let small = gc.create(Blob(vec![0; 16])).as_weak();
+
+        // 普通回收不回收大对象
+        gc.collect();
+        assert!(large.is_valid());
+        assert!(!small.is_valid());
+
+        gc.collect_major();
+        assert!(!large.is_valid());
+        assert_eq!(gc.object_count(), 0);
+    }
+
+    #[test]
+    fn test_live_weak_summary() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let a = gc.create(ValueObject(1));
+        let _b = gc.create(ValueObject(2));
+        let weak_a = a.as_weak();
+        let _weak_a2 = a.as_weak();
+        gc.freeze_subgraph(&a);
+        // 冻结子图持有的弱引用不计入
+        assert_eq!(gc.live_weak_summary(), (2, 0));
+        drop(weak_a);
+        assert_eq!(gc.live_weak_summary(), (1, 0));
+
+        let mut gc: GC<ValueObject> = GC::new_weak_tracking();
+        let c = gc.create(ValueObject(3));
+        let _weak_c = c.as_weak();
+        let _ = gc.create(ValueObject(4));
+        assert_eq!(gc.live_weak_summary(), (1, 1));
+    }
+
+    #[test]
+    fn test_get_all_outstanding() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let _ = gc.create(ValueObject(1));
+        let _ = gc.create(ValueObject(2));
+
+        let mut values = Vec::new();
+        gc.for_each(|r| values.push(r.as_ref().0));
+        values.sort();
+        assert_eq!(values, vec![1, 2]);
+
+        let all = gc.get_all_tracked();
+        assert_eq!(all.len(), 2);
+        let copy = all.clone();
+        gc.collect();
+        // 未丢弃的结果使对象被视为根对象
+        assert_eq!(gc.object_count(), 2);
+        assert_eq!(gc.last_collection_stats().outstanding_get_all, 2);
+
+        drop(copy);
+        let mut ids: Vec<u64> = all.into_iter().map(|r| r.id()).collect();
+        ids.dedup();
+        assert_eq!(ids.len(), 2);
+        gc.collect();
+        assert_eq!(gc.object_count(), 0);
+        assert_eq!(gc.last_collection_stats().outstanding_get_all, 0);
+
+        let _ = gc.create(ValueObject(3));
+        let all: Vec<GCArc<ValueObject>> = gc.get_all();
+        assert_eq!(all.len(), 1);
+    }
+
+    #[test]
+    fn test_collect_subtree() {
+        let mut gc: GC<TestObjectCell> = GC::new();
+        gc.set_auto_collect(false);
+        let new_cell = |value| TestObjectCell(RefCell::new(TestObject { value }));
+
+        // scene -> child -> shared，other -> shared；另有一个不相关的垃圾对象
+        let shared = gc.create(new_cell(None));
+        let child = gc.create(new_cell(Some(shared.as_weak())));
+        let scene = gc.create(new_cell(Some(child.as_weak())));
+        let other = gc.create(new_cell(Some(shared.as_weak())));
+        let unrelated = gc.create(new_cell(None)).as_weak();
+        let shared_weak = shared.as_weak();
+        let child_weak = child.as_weak();
+        drop(shared);
+        drop(child);
+
+        assert_eq!(gc.collect_subtree(&scene), 2);
+        assert!(!child_weak.is_valid());
+        assert!(shared_weak.is_valid());
+        assert!(unrelated.is_valid());
+        assert!(!gc.contains(&scene));
+        assert!(gc.contains(&other));
+        assert_eq!(gc.object_count(), 3);
+    }
+
+    #[test]
+    fn test_default_weak() {
+        let weak: GCArcWeak<ValueObject> = GCArcWeak::default();
+        assert!(weak.upgrade().is_none());
+        assert!(!weak.is_valid());
+    }
+
+    #[test]
+    fn test_min_garbage_coalescing() {
+        let collections = Arc::new(AtomicUsize::new(0));
+        let mut gc: GC<ValueObject> = GC::new_with_percentage(1);
+        let counter = collections.clone();
+        gc.on_post_sweep(move |_| {
+            counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        });
+        gc.set_min_garbage(Some(10));
+
+        let mut kept = Vec::new();
+        for i in 0..50 {
+            kept.push(gc.create(ValueObject(i)));
+        }
+        // 没有最小收益时几乎每次attach都会触发回收
+        assert_eq!(collections.load(std::sync::atomic::Ordering::Relaxed), 5);
+        assert_eq!(gc.object_count(), 50);
+    }
+
+    #[test]
+    fn test_attach_scoped() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let temp = GCArc::new(ValueObject(1));
+        {
+            let mut scope = gc.attach_scoped(&temp);
+            assert!(scope.contains(&temp));
+            assert_eq!(
+                temp.inner()
+                    .attached_gc_count
+                    .load(std::sync::atomic::Ordering::Relaxed),
+                1
+            );
+            scope.collect();
+            assert_eq!(scope.object_count(), 1);
+        }
+        assert!(!gc.contains(&temp));
+        assert_eq!(gc.object_count(), 0);
+        assert_eq!(gc.allocated_memory(), 0);
+        assert_eq!(
+            temp.inner()
                 .attached_gc_count
-                .fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
-            
-            // 从内存计数中减去对象大小
-            let obj_size = std::mem::size_of::<T>() + std::mem::size_of::<GCArc<T>>();
-            self.allocated_memory
-                .fetch_sub(obj_size, std::sync::atomic::Ordering::Relaxed);
-                
-            // 直接调用 `drop` 方法，确保所有对象都被正确释放。
-            // 这将触发每个对象的 `Drop` 实现。
-            drop(gc_arc);
+                .load(std::sync::atomic::Ordering::Relaxed),
+            0
+        );
+        assert_eq!(temp.strong_ref(), 1);
+    }
+
+    #[test]
+    fn test_null_dead_weaks() {
+        struct Node(RefCell<Vec<GCArcWeak<Node>>>);
+        impl GCTraceable<Node> for Node {
+            fn collect(&self, _queue: &mut VecDeque<GCArcWeak<Node>>) {}
+
+            fn prune_dead_weaks(&self) {
+                self.0.borrow_mut().retain(|weak| weak.is_valid());
+            }
         }
+
+        let mut gc: GC<Node> = GC::new();
+        gc.set_auto_collect(false);
+        gc.set_null_dead_weaks(true);
+        let holder = gc.create(Node(RefCell::new(Vec::new())));
+        let kept = gc.create(Node(RefCell::new(Vec::new())));
+        let dropped = gc.create(Node(RefCell::new(Vec::new())));
+        holder
+            .as_ref()
+            .0
+            .borrow_mut()
+            .extend([kept.as_weak(), dropped.as_weak()]);
+        drop(dropped);
+
+        gc.collect();
+        assert_eq!(holder.as_ref().0.borrow().len(), 1);
+        assert!(holder.as_ref().0.borrow()[0].is_valid());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::cell::RefCell;
+    #[test]
+    #[allow(clippy::mutable_key_type)] // ById 按id排序，内部可变性不影响顺序
+    fn test_by_id_ord() {
+        use crate::arc::ById;
+        use std::collections::BTreeSet;
 
-    use super::*;
-    use crate::{arc::GCArcWeak, traceable::GCTraceable};
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let objects: Vec<GCArc<ValueObject>> = (0..5).map(|i| gc.create(ValueObject(i))).collect();
 
-    struct TestObject {
-        value: Option<GCArcWeak<TestObjectCell>>,
+        let set: BTreeSet<ById<ValueObject>> = objects.iter().rev().cloned().map(ById).collect();
+        let ids: Vec<u64> = set.iter().map(|r| r.0.id()).collect();
+        let mut expected: Vec<u64> = objects.iter().map(|r| r.id()).collect();
+        expected.sort_unstable();
+        assert_eq!(ids, expected);
+        assert!(ById(objects[0].clone()) < ById(objects[1].clone()));
+        assert_eq!(
+            ById(objects[2].clone()).cmp(&ById(objects[2].clone())),
+            std::cmp::Ordering::Equal
+        );
     }
 
-    impl GCTraceable<TestObjectCell> for TestObject {
-        fn collect(&self, queue: &mut VecDeque<GCArcWeak<TestObjectCell>>) {
-            if let Some(ref weak_ref) = self.value {
-                queue.push_back(weak_ref.clone());
+    #[test]
+    fn test_marking_keeps_weak_counts_stable() {
+        struct Node {
+            edge: Option<GCArcWeak<Node>>,
+            probe: RefCell<Option<GCArcWeak<Node>>>,
+            seen_weak_count: std::cell::Cell<Option<usize>>,
+        }
+        impl GCTraceable<Node> for Node {
+            fn collect(&self, queue: &mut VecDeque<GCArcWeak<Node>>) {
+                // 在标记进行中读取被观察对象的弱引用计数
+                if let Some(probe) = self.probe.borrow().as_ref() {
+                    self.seen_weak_count.set(Some(probe.weak_ref()));
+                }
+                queue.extend(self.edge.iter().cloned());
             }
         }
+        let new_node = |edge| Node {
+            edge,
+            probe: RefCell::new(None),
+            seen_weak_count: std::cell::Cell::new(None),
+        };
+
+        let mut gc: GC<Node> = GC::new();
+        gc.set_auto_collect(false);
+        // `child` 只能经由 `observer` 到达，并观察自身；`observer` 观察 `root`。
+        // `observer` 先于 `root` 被追踪，遍历 `observer` 时 `root` 仍在标记队列中
+        let child = gc.create(new_node(None));
+        *child.as_ref().probe.borrow_mut() = Some(child.as_weak());
+        let observer = gc.create(new_node(Some(child.as_weak())));
+        let root = gc.create(new_node(None));
+        *observer.as_ref().probe.borrow_mut() = Some(root.as_weak());
+        drop(child);
+
+        // 标记队列不为根对象和被追踪的子对象持有弱引用：
+        // `root` 只被 `observer` 的探针引用，`child` 只被自身的探针和 `observer` 的边引用
+        gc.collect();
+        assert_eq!(observer.as_ref().seen_weak_count.get(), Some(1));
+        let child = observer.as_ref().edge.as_ref().unwrap().upgrade().unwrap();
+        assert_eq!(child.as_ref().seen_weak_count.get(), Some(2));
+        assert_eq!(gc.object_count(), 3);
+        drop(root);
     }
 
-    impl Drop for TestObject {
-        fn drop(&mut self) {
-            println!("Dropping TestObject: address={:p}", self);
+    #[test]
+    fn test_tracked_by_count() {
+        let obj = GCArc::new(ValueObject(1));
+        assert_eq!(obj.tracked_by_count(), 0);
+
+        let mut first: GC<ValueObject> = GC::new();
+        let mut second: GC<ValueObject> = GC::new();
+        first.attach(&obj);
+        second.attach(&obj);
+        assert_eq!(obj.tracked_by_count(), 2);
+        // 两个GC各持有一份强引用，多出的一份来自外部，因此是根对象
+        assert!(obj.strong_ref() > obj.tracked_by_count());
+
+        first.detach(&obj);
+        assert_eq!(obj.tracked_by_count(), 1);
+    }
+
+    #[test]
+    fn test_collect_keeping() {
+        let mut gc: GC<TestObjectCell> = GC::new();
+        gc.set_auto_collect(false);
+        let new_cell = |value| TestObjectCell(RefCell::new(TestObject { value }));
+
+        let child = gc.create(new_cell(None)).as_weak();
+        let task_object = gc.create(new_cell(Some(child.clone()))).as_weak();
+        let unrelated = gc.create(new_cell(None)).as_weak();
+        let dead = GCArcWeak::default();
+
+        gc.collect_keeping(&[task_object.clone(), dead]);
+        assert!(task_object.is_valid());
+        assert!(child.is_valid());
+        assert!(!unrelated.is_valid());
+
+        // 保留只对本次回收有效
+        gc.collect();
+        assert!(!task_object.is_valid());
+        assert_eq!(gc.object_count(), 0);
+    }
+
+    #[test]
+    fn test_alloc_size() {
+        let obj = GCArc::new(ValueObject(1));
+        let wrapper = std::mem::size_of::<crate::arc::GCWrapper<ValueObject>>();
+        assert_eq!(obj.alloc_size(), wrapper + 2 * std::mem::size_of::<usize>());
+        assert!(obj.alloc_size() > std::mem::size_of::<ValueObject>());
+    }
+
+    #[test]
+    fn test_keep_while() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let external = Arc::new(());
+        let observer = Arc::downgrade(&external);
+        let obj = gc.create(ValueObject(1));
+        gc.keep_while(&obj, move || observer.strong_count() > 0);
+        let weak = obj.as_weak();
+        drop(obj);
+
+        gc.collect();
+        assert!(weak.is_valid());
+
+        drop(external);
+        gc.collect();
+        assert!(!weak.is_valid());
+        assert!(gc.liveness.lock().unwrap().is_empty());
+
+        // 移出追踪的对象不再需要存活条件
+        let obj = gc.create(ValueObject(2));
+        gc.keep_while(&obj, || true);
+        gc.detach(&obj);
+        assert!(gc.liveness.lock().unwrap().is_empty());
+        gc.attach(&obj);
+        gc.keep_while(&obj, || true);
+        drop(gc.drain());
+        assert!(gc.liveness.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_weak_alias() {
+        let obj = GCArc::new(ValueObject(1));
+        let weak = obj.weak();
+        assert!(obj.same_object_as(&weak));
+        assert_eq!(obj.weak_ref(), 1);
+        assert!(GCArc::ptr_eq(&weak.upgrade().unwrap(), &obj));
+    }
+
+    #[test]
+    fn test_collect_with_scanner() {
+        struct StackScanner(Vec<GCArcWeak<TestObjectCell>>);
+        impl RootScanner<TestObjectCell> for StackScanner {
+            fn scan_roots(
+                &self,
+                _: &GC<TestObjectCell>,
+                _: &[GCArc<TestObjectCell>],
+                sink: &mut VecDeque<GCArcWeak<TestObjectCell>>,
+            ) {
+                sink.extend(self.0.iter().cloned());
+            }
         }
+
+        let mut gc: GC<TestObjectCell> = GC::new();
+        gc.set_auto_collect(false);
+        let new_cell = |value| TestObjectCell(RefCell::new(TestObject { value }));
+        let child = gc.create(new_cell(None)).as_weak();
+        let on_stack = gc.create(new_cell(Some(child.clone()))).as_weak();
+        let held = gc.create(new_cell(None));
+
+        // 只有扫描器报告的根对象存活，外部持有但未被报告的对象被移出追踪
+        gc.collect_with_scanner(&StackScanner(vec![on_stack.clone()]));
+        assert!(on_stack.is_valid());
+        assert!(child.is_valid());
+        assert!(!gc.contains(&held));
+        assert_eq!(gc.object_count(), 2);
+
+        // 默认扫描器与 `collect()` 的根对象判断相同：固定的对象同样是根对象
+        gc.attach(&held);
+        let pinned = gc.create(new_cell(None)).as_weak();
+        pinned.upgrade().unwrap().set_pinned(true);
+        let scanner = RefcountRootScanner;
+        gc.collect_with_scanner(&scanner);
+        assert!(!on_stack.is_valid());
+        assert!(pinned.is_valid());
+        assert!(gc.contains(&held));
+        assert_eq!(gc.object_count(), 2);
+
+        // 根对象在每次回收时重新判断：同一个扫描器再次使用时仍报告固定的对象，
+        // 并反映上次回收之后外部引用的释放
+        let held_weak = held.as_weak();
+        drop(held);
+        gc.collect_with_scanner(&scanner);
+        assert!(pinned.is_valid());
+        assert!(!held_weak.is_valid());
+        assert_eq!(gc.object_count(), 1);
     }
 
-    struct TestObjectCell(RefCell<TestObject>);
-    impl GCTraceable<TestObjectCell> for TestObjectCell {
-        fn collect(&self, queue: &mut VecDeque<GCArcWeak<TestObjectCell>>) {
-            if let Ok(obj) = self.0.try_borrow() {
-                if let Some(ref weak_ref) = obj.value {
-                    queue.push_back(weak_ref.clone());
-                }
+    #[test]
+    fn test_is_collectible() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let obj = gc.create(ValueObject(1));
+        assert_eq!(obj.external_strong_count(), 1);
+        assert!(!obj.is_collectible());
+
+        // 通过 `for_each` 访问GC持有的引用，不产生外部强引用
+        drop(obj);
+        let mut collectible = Vec::new();
+        gc.for_each(|r| collectible.push(r.is_collectible()));
+        assert_eq!(collectible, vec![true]);
+
+        gc.for_each(|r| r.set_pinned(true));
+        collectible.clear();
+        gc.for_each(|r| collectible.push(r.is_collectible()));
+        assert_eq!(collectible, vec![false]);
+    }
+
+    #[test]
+    fn test_for_each_weak_tracking() {
+        let mut gc: GC<ValueObject> = GC::new_weak_tracking();
+        let kept = gc.create(ValueObject(1));
+        let _ = gc.create(ValueObject(2));
+
+        let mut weaks = Vec::new();
+        gc.for_each(|r| weaks.push(r.as_weak()));
+        assert_eq!(weaks.len(), 1);
+        assert!(kept.same_object_as(&weaks[0]));
+    }
+
+    #[test]
+    fn test_fragmentation_estimate() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        assert_eq!(gc.fragmentation_estimate(), 0.0);
+
+        gc.reserve(4);
+        let objects: Vec<GCArc<ValueObject>> = (0..4).map(|i| gc.create(ValueObject(i))).collect();
+        let capacity = gc.spare_capacity() + gc.object_count();
+        // 大小相同的对象没有离散程度，只剩追踪列表的空闲比例
+        let expected = 1.0 - 4.0 / capacity as f64;
+        assert!((gc.fragmentation_estimate() - expected).abs() < 1e-9);
+
+        drop(objects);
+        gc.collect();
+        assert_eq!(gc.fragmentation_estimate(), 1.0);
+    }
+
+    #[test]
+    fn test_weak_try_from() {
+        use crate::error::WeakUpgradeError;
+
+        fn first_value(weak: &GCArcWeak<ValueObject>) -> Result<i32, WeakUpgradeError> {
+            let arc: GCArc<ValueObject> = weak.try_into()?;
+            Ok(arc.as_ref().0)
+        }
+
+        let obj = GCArc::new(ValueObject(7));
+        let weak = obj.as_weak();
+        assert_eq!(first_value(&weak), Ok(7));
+        drop(obj);
+        assert_eq!(first_value(&weak), Err(WeakUpgradeError));
+        assert!(GCArc::try_from(weak).is_err());
+    }
+
+    #[test]
+    fn test_prune() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let obj = gc.create(ValueObject(1));
+        assert_eq!(gc.prune(), 0);
+
+        // 人为制造簿记错误：重复追踪和残留的标记位
+        gc.attach(&obj);
+        obj.inner()
+            .marked
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+        assert!(gc.validate().is_err());
+        assert_eq!(gc.prune(), 3);
+        assert!(gc.validate().is_ok());
+        assert_eq!(obj.tracked_by_count(), 1);
+
+        let mut gc: GC<ValueObject> = GC::new_weak_tracking();
+        let _ = gc.create(ValueObject(2));
+        assert_eq!(gc.prune(), 2);
+        assert_eq!(gc.object_count(), 0);
+        assert_eq!(gc.allocated_memory(), 0);
+    }
+
+    #[test]
+    fn test_new_uninit() {
+        struct SelfRef {
+            me: GCArcWeak<SelfRef>,
+            value: i32,
+        }
+
+        impl GCTraceable<SelfRef> for SelfRef {
+            fn collect(&self, queue: &mut VecDeque<GCArcWeak<SelfRef>>) {
+                queue.push_back(self.me.clone());
             }
         }
+
+        let uninit = GCArc::<SelfRef>::new_uninit();
+        let me = unsafe { uninit.as_weak_init() };
+        unsafe { uninit.as_mut_ptr().write(SelfRef { me, value: 3 }) };
+        let obj = unsafe { uninit.assume_init() };
+        assert_eq!(obj.as_ref().value, 3);
+        assert!(GCArc::ptr_eq(&obj.as_ref().me.upgrade().unwrap(), &obj));
+
+        let mut gc: GC<SelfRef> = GC::new();
+        gc.set_auto_collect(false);
+        gc.attach(&obj);
+        let weak = obj.as_weak();
+        drop(obj);
+        gc.collect();
+        assert!(!weak.is_valid());
+        assert_eq!(gc.object_count(), 0);
     }
-    impl Drop for TestObjectCell {
-        fn drop(&mut self) {
-            println!("Dropping TestObjectCell: address={:p}", self);
+
+    #[test]
+    fn test_adaptive_percentage() {
+        let mut gc: GC<ValueObject> = GC::new_with_percentage(20);
+        gc.set_auto_collect(false);
+        gc.set_adaptive(true);
+        let kept: Vec<_> = (0..100).map(|i| gc.create(ValueObject(i))).collect();
+        gc.collect();
+        gc.collect();
+        let raised = gc.collection_percentage();
+        assert!(raised > 20);
+
+        for round in 0..10 {
+            for i in 0..1000 {
+                let _ = gc.create(ValueObject(round * 1000 + i));
+            }
+            gc.collect();
         }
+        assert!(gc.collection_percentage() < raised);
+        assert!(gc.collection_percentage() >= 5);
+        assert_eq!(gc.object_count(), kept.len());
+
+        gc.set_adaptive(false);
+        let fixed = gc.collection_percentage();
+        gc.collect();
+        assert_eq!(gc.collection_percentage(), fixed);
     }
 
     #[test]
-    fn test_gc() {
-        let mut gc: GC<TestObjectCell> = GC::new_with_percentage(20);
+    fn test_watch_refcount() {
+        let mut gc: GC<ValueObject> = GC::new();
+        let obj = gc.create(ValueObject(1));
         {
-            let obj1 = gc.create(TestObjectCell {
-                0: RefCell::new(TestObject { value: None }),
-            });
-            let weak_ref = obj1.as_weak();
-            match obj1.as_ref().0.try_borrow_mut() {
-                Ok(mut obj) => {
-                    obj.value = Some(weak_ref);
-                }
-                Err(_) => {
-                    panic!("Failed to borrow TestObjectCell mutably");
-                }
+            let _guard = obj.watch();
+            let temporary = obj.clone();
+            drop(temporary);
+        }
+
+        let mut leaked = Vec::new();
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let guard = obj.watch();
+            assert_eq!(guard.expected(), 1);
+            leaked.push(obj.clone());
+        }));
+        assert!(result.is_err());
+        assert_eq!(obj.external_strong_count(), 2);
+    }
+
+    #[test]
+    fn test_with_config() {
+        let gc: GC<ValueObject> = GC::with_config(GCConfig::default());
+        assert_eq!(gc.collection_percentage(), 20);
+        assert_eq!(gc.memory_threshold(), None);
+
+        let mut gc: GC<ValueObject> = GC::with_config(GCConfig {
+            collection_percentage: 50,
+            memory_threshold: Some(1 << 20),
+            initial_capacity: 64,
+            auto_collect: false,
+            min_interval: Some(Duration::from_secs(1)),
+        });
+        assert_eq!(gc.collection_percentage(), 50);
+        assert_eq!(gc.memory_threshold(), Some(1 << 20));
+        assert_eq!(gc.min_interval(), Some(Duration::from_secs(1)));
+        for i in 0..10 {
+            let _ = gc.create(ValueObject(i));
+        }
+        assert_eq!(gc.object_count(), 10);
+    }
+
+    #[test]
+    fn test_arc_reaches() {
+        let a = GCArc::new(TestObjectCell(RefCell::new(TestObject { value: None })));
+        let b = GCArc::new(TestObjectCell(RefCell::new(TestObject { value: None })));
+        let c = GCArc::new(TestObjectCell(RefCell::new(TestObject { value: None })));
+        a.as_ref().0.borrow_mut().value = Some(b.as_weak());
+        b.as_ref().0.borrow_mut().value = Some(c.as_weak());
+
+        assert!(a.reaches(&c, 10));
+        assert!(a.reaches(&a, 1));
+        assert!(!c.reaches(&a, 10));
+        assert!(!a.reaches(&c, 1));
+    }
+
+    #[test]
+    fn test_event_sink() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let (sender, receiver) = std::sync::mpsc::sync_channel(16);
+        gc.set_event_sink(Some(sender));
+        let kept = gc.create(ValueObject(1));
+        let freed = gc.create(ValueObject(2)).id();
+        gc.collect();
+
+        let events: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0], CollectionEvent::CollectionStarted);
+        assert!(matches!(events[1], CollectionEvent::ObjectFreed { id, bytes } if id == freed && bytes > 0));
+        assert!(matches!(events[2], CollectionEvent::CollectionFinished(_)));
+
+        // 通道已满时丢弃事件而不阻塞回收
+        let (sender, receiver) = std::sync::mpsc::sync_channel(1);
+        gc.set_event_sink(Some(sender));
+        for i in 0..5 {
+            let _ = gc.create(ValueObject(i));
+        }
+        gc.collect();
+        assert_eq!(receiver.try_iter().count(), 1);
+        drop(receiver);
+        gc.collect();
+        assert_eq!(gc.object_count(), 1);
+        drop(kept);
+    }
+
+    #[test]
+    fn test_trace_skips_duplicate_children() {
+        struct Node {
+            edges: Vec<GCArcWeak<Node>>,
+        }
+
+        impl GCTraceable<Node> for Node {
+            fn collect(&self, queue: &mut VecDeque<GCArcWeak<Node>>) {
+                queue.extend(self.edges.iter().cloned());
             }
-            print!("GC object count before collection: {}\n", gc.object_count());
         }
+
+        const PARENTS: usize = 20;
+        const SHARED: usize = 20;
+        let mut gc: GC<Node> = GC::new();
+        gc.set_auto_collect(false);
+        let shared: Vec<_> = (0..SHARED)
+            .map(|_| gc.create(Node { edges: Vec::new() }))
+            .collect();
+        let parents: Vec<_> = (0..PARENTS)
+            .map(|_| {
+                gc.create(Node {
+                    edges: shared.iter().map(|s| s.as_weak()).collect(),
+                })
+            })
+            .collect();
+        let root = gc.create(Node {
+            edges: parents.iter().map(|p| p.as_weak()).collect(),
+        });
+        drop(shared);
+        drop(parents);
+
         gc.collect();
-        println!("GC completed, all objects should be dropped now.");
+        assert_eq!(gc.object_count(), 1 + PARENTS + SHARED);
+        // 每个共享对象被所有父对象引用，但在队列中最多出现一次
+        assert!(gc.last_collection_stats().max_queue_len <= PARENTS + SHARED);
+        drop(root);
+        gc.collect();
+        assert_eq!(gc.object_count(), 0);
     }
 
     #[test]
-    fn test_memory_threshold_gc() {
-        // 使用较小的内存阈值（1KB）来测试内存触发
-        let mut gc: GC<TestObjectCell> = GC::new_with_memory_threshold(1024);
-        
-        println!("Initial allocated memory: {} bytes", gc.allocated_memory());
-        
-        // 创建多个对象直到触发内存阈值
-        let mut objects = Vec::new();
-        for i in 0..50 {
-            let obj = gc.create(TestObjectCell {
-                0: RefCell::new(TestObject { value: None }),
-            });
-            objects.push(obj);
-            
-            println!("After creating object {}: allocated={} bytes, object_count={}", 
-                    i + 1, gc.allocated_memory(), gc.object_count());
-            
-            if gc.allocated_memory() > 1024 {
-                break;
+    fn test_collect_empty_heap_skips_root_scan() {
+        struct CountingScanner(std::sync::atomic::AtomicUsize);
+        impl RootScanner<ValueObject> for CountingScanner {
+            fn scan_roots(
+                &self,
+                _: &GC<ValueObject>,
+                _: &[GCArc<ValueObject>],
+                _: &mut VecDeque<GCArcWeak<ValueObject>>,
+            ) {
+                self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
             }
         }
-        
-        println!("Before collection: allocated={} bytes, object_count={}", 
-                gc.allocated_memory(), gc.object_count());
-        
-        // 释放引用，让对象变成垃圾
-        objects.clear();
-        
-        // 手动触发回收
+
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let scanner = CountingScanner(std::sync::atomic::AtomicUsize::new(0));
+
+        // 新建的GC未被attach过，追踪列表为空时不扫描根对象
+        gc.collect_with_scanner(&scanner);
+        assert_eq!(scanner.0.load(std::sync::atomic::Ordering::Relaxed), 0);
+        assert!(!*gc.dirty.get_mut());
+
+        // attach之后需要扫描；全部detach后再次为空，确认为空后清除 `dirty`
+        let obj = gc.create(ValueObject(1));
+        assert!(*gc.dirty.get_mut());
+        gc.collect_with_scanner(&scanner);
+        assert_eq!(scanner.0.load(std::sync::atomic::Ordering::Relaxed), 1);
+        gc.attach(&obj);
+        gc.detach(&obj);
+        gc.collect_with_scanner(&scanner);
         gc.collect();
-        
-        println!("After collection: allocated={} bytes, object_count={}", 
-                gc.allocated_memory(), gc.object_count());
+        assert_eq!(scanner.0.load(std::sync::atomic::Ordering::Relaxed), 1);
+        assert!(!*gc.dirty.get_mut());
+        assert_eq!(gc.last_collection_stats(), CollectionStats::default());
     }
 
     #[test]
-    fn test_combined_thresholds_gc() {
-        // 测试同时使用百分比和内存阈值
-        let mut gc: GC<TestObjectCell> = GC::new_with_thresholds(50, 2048); // 50%或2KB
-        
-        println!("Testing combined thresholds: 50% or 2KB");
-        
-        let obj1 = gc.create(TestObjectCell {
-            0: RefCell::new(TestObject { value: None }),
-        });
-        
-        println!("Memory threshold: {:?}", gc.memory_threshold());
-        println!("Allocated memory: {} bytes", gc.allocated_memory());
-        println!("Object count: {}", gc.object_count());
-        
-        // 保持引用以防止被回收
-        let _keep_ref = obj1;
+    fn test_resurrection_with_edge_release() {
+        let sink = Arc::new(Mutex::new(Vec::new()));
+        let mut gc: GC<Phoenix> = GC::new();
+        gc.set_auto_collect(false);
+        // `first` 持有指向 `second` 的强引用边，析构时复活 `second` 后才释放这条边：
+        // `second` 的强引用计数先加1再减1，释放前后的计数相同，但它已被 `sink` 持有
+        let second = GCArc::new(Phoenix::new(None, &sink));
+        let second_id = second.id();
+        let mut first = Phoenix::new(Some(second.as_weak()), &sink);
+        first.held = Some(second.clone());
+        let first = gc.create(first);
+        gc.attach(&second);
+        drop((first, second));
+
+        gc.collect();
+        assert_eq!(gc.object_count(), 1);
+        assert_eq!(sink.lock().unwrap()[0].id(), second_id);
+        assert_eq!(gc.validate(), Ok(()));
+        drop(gc);
+        sink.lock().unwrap().clear();
+    }
+
+    #[test]
+    fn test_import_state_keeps_allocated_memory() {
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let objs: Vec<_> = (0..4).map(|i| gc.create(ValueObject(i))).collect();
+        let state = gc.export_state();
+
+        // 只重新attach部分对象，导入的快照不应改变当前分配量
+        let mut restored: GC<ValueObject> = GC::new();
+        restored.set_auto_collect(false);
+        restored.attach_many(objs[..2].iter().cloned());
+        let allocated = restored.allocated_memory();
+        restored.import_state(state);
+        assert_eq!(restored.allocated_memory(), allocated);
+        assert_eq!(restored.total_allocated_bytes(), state.total_allocated);
+
+        for obj in &objs[..2] {
+            assert!(restored.detach(obj));
+        }
+        assert_eq!(restored.allocated_memory(), 0);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_tracing_spans() {
+        use tracing::{
+            field::{Field, Visit},
+            span, Event, Metadata, Subscriber,
+        };
+
+        type SpanLog = Arc<Mutex<Vec<(&'static str, Vec<(&'static str, u64)>)>>>;
+
+        /// 记录每个 span 的名称及其数值字段
+        struct Recorder(SpanLog);
+
+        struct Fields<'a>(&'a mut Vec<(&'static str, u64)>);
+
+        impl Visit for Fields<'_> {
+            fn record_u64(&mut self, field: &Field, value: u64) {
+                self.0.push((field.name(), value));
+            }
+
+            fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+        }
+
+        impl Subscriber for Recorder {
+            fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+                let mut spans = self.0.lock().unwrap();
+                let mut fields = Vec::new();
+                span.record(&mut Fields(&mut fields));
+                spans.push((span.metadata().name(), fields));
+                span::Id::from_u64(spans.len() as u64)
+            }
+
+            fn record(&self, span: &span::Id, values: &span::Record<'_>) {
+                let mut spans = self.0.lock().unwrap();
+                values.record(&mut Fields(&mut spans[span.into_u64() as usize - 1].1));
+            }
+
+            fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+            fn event(&self, _event: &Event<'_>) {}
+
+            fn enter(&self, _span: &span::Id) {}
+
+            fn exit(&self, _span: &span::Id) {}
+        }
+
+        let log = SpanLog::default();
+        let mut gc: GC<ValueObject> = GC::new();
+        gc.set_auto_collect(false);
+        let _kept = gc.create(ValueObject(0));
+        for i in 1..4 {
+            let _ = gc.create(ValueObject(i));
+        }
+        tracing::subscriber::with_default(Recorder(log.clone()), || gc.collect());
+
+        let spans = log.lock().unwrap();
+        assert_eq!(
+            *spans,
+            vec![
+                ("mark", vec![("tracked", 4), ("scanned", 1)]),
+                ("sweep", vec![("freed", 3)]),
+            ]
+        );
     }
+
 }
@@ -1,3 +1,11 @@
+pub mod any;
 pub mod arc;
+#[cfg(feature = "bench-utils")]
+pub mod bench_utils;
+#[cfg(feature = "debug")]
+pub mod debug;
+#[cfg(feature = "epoch")]
+pub mod epoch;
+pub mod error;
 pub mod gc;
 pub mod traceable;
@@ -1,8 +1,66 @@
 use std::collections::VecDeque;
 
-use crate::arc::GCArcWeak;
+use crate::arc::{GCArc, GCArcWeak};
+
+/// 对象向外的一条引用边
+pub enum Edge<T: GCTraceable<T> + 'static> {
+    /// 强引用边：子对象的强引用计数中包含这条边，标记时不会因此把子对象误判为根对象
+    Strong(GCArc<T>),
+    /// 弱引用边：仅用于遍历
+    Weak(GCArcWeak<T>),
+}
 
 pub trait GCTraceable<T: GCTraceable<T> + 'static> {
+    /// 为 `true` 时GC通过 `edges` 遍历对象图，并在识别根对象时扣除来自被追踪对象的强引用边
+    /// 持有 `GCArc` 子对象的类型应将其设为 `true` 并实现 `edges`
+    const TRACES_STRONG_EDGES: bool = false;
+
     /// collects all reachable objects and adds them to the provided queue.
     fn collect(&self, queue: &mut VecDeque<GCArcWeak<T>>);
+
+    /// 报告所有带类型的引用边，默认将 `collect` 收集到的弱引用作为 `Edge::Weak` 报告
+    fn edges(&self, edges: &mut Vec<Edge<T>>) {
+        let mut queue = VecDeque::new();
+        self.collect(&mut queue);
+        edges.extend(queue.into_iter().map(Edge::Weak));
+    }
+
+    /// 对象占用内存的估算值（字节），默认为值本身的大小
+    /// 持有堆上数据（如 `Vec`、`String`）的类型可以重写此方法以反映真实占用
+    fn size_hint(&self) -> usize {
+        std::mem::size_of_val(self)
+    }
+
+    /// 将对象持有的引用边按 `remap` 重定向：`remap` 对需要改写的边返回新的目标，否则返回 `None`
+    /// 只由 `GC::dedup_by` 调用；对象是共享的，因此实现需要借助内部可变性。默认不改写任何边
+    fn rewrite_edges(&self, _remap: &dyn Fn(&GCArcWeak<T>) -> Option<GCArcWeak<T>>) {}
+
+    /// 丢弃目标已被释放的弱引用边，由开启了 `GC::set_null_dead_weaks` 的GC在回收后对每个存活对象调用
+    /// 对象是共享的，因此实现需要借助内部可变性。默认不做任何事
+    fn prune_dead_weaks(&self) {}
+
+    /// 丢弃对象持有的所有强引用边，由GC在释放被清除的 `TRACES_STRONG_EDGES` 对象之前调用，
+    /// 使被回收的强引用环能够真正被释放。对象是共享的，因此实现需要借助内部可变性。默认不做任何事
+    fn release_strong_edges(&self) {}
+
+    /// 清空对象持有的所有向外引用
+    /// 只由 `GC::break_cycles` 对即将被释放的对象调用，使长链在释放时不会产生深度递归的 `Drop`。默认不做任何事
+    fn clear_edges(&mut self) {}
+}
+
+/// 将对象引用的所有子对象加入遍历队列，强引用边和弱引用边都会被跟随
+pub(crate) fn trace_children<T>(value: &T, queue: &mut VecDeque<GCArcWeak<T>>)
+where
+    T: GCTraceable<T> + 'static,
+{
+    if !T::TRACES_STRONG_EDGES {
+        value.collect(queue);
+        return;
+    }
+    let mut edges = Vec::new();
+    value.edges(&mut edges);
+    queue.extend(edges.into_iter().map(|edge| match edge {
+        Edge::Strong(child) => child.as_weak(),
+        Edge::Weak(child) => child,
+    }));
 }